@@ -0,0 +1,95 @@
+//! compare the scanline/incremental rasterizers against the per-pixel ones
+//!
+//! `cargo bench --bench rasterize`
+
+fn measure<F: FnMut()>(name: &str, num_itr: usize, mut f: F) -> std::time::Duration {
+    f(); // warm up
+    let start = std::time::Instant::now();
+    for _ in 0..num_itr {
+        f();
+    }
+    let t = start.elapsed() / num_itr as u32;
+    println!("{:<40} {:>12.3?}", name, t);
+    t
+}
+
+fn main() {
+    let img_size = (512usize, 512usize);
+    let trans_world2pix = del_canvas::cam2::transform_world2pix_ortho_preserve_asp(
+        &img_size,
+        &[-0.1, -0.1, 1.1, 1.1],
+    );
+    {
+        // star-shaped polygon with many vertices
+        let num_vtx = 200;
+        let vtx2xy: Vec<f32> = (0..num_vtx)
+            .flat_map(|i_vtx| {
+                let theta = 2. * std::f32::consts::PI * i_vtx as f32 / num_vtx as f32;
+                let r = if i_vtx % 2 == 0 { 0.5 } else { 0.35 };
+                [0.5 + r * theta.cos(), 0.5 + r * theta.sin()]
+            })
+            .collect();
+        let mut img_data = vec![0u8; img_size.0 * img_size.1];
+        let t0 = measure("polygon2::fill_per_pixel", 3, || {
+            del_canvas::rasterize::polygon2::fill_per_pixel(
+                &mut img_data,
+                img_size.0,
+                &vtx2xy,
+                &trans_world2pix,
+                1u8,
+            );
+        });
+        let t1 = measure("polygon2::fill_scanline", 100, || {
+            del_canvas::rasterize::polygon2::fill_scanline(
+                &mut img_data,
+                img_size.0,
+                &vtx2xy,
+                &trans_world2pix,
+                1u8,
+            );
+        });
+        println!("speedup: {:.1}x", t0.as_secs_f64() / t1.as_secs_f64());
+        std::hint::black_box(&img_data);
+    }
+    {
+        // grid of thin triangles
+        let num_div = 64;
+        let mut tris = vec![];
+        for i in 0..num_div {
+            for j in 0..num_div {
+                let (x, y) = (i as f32 / num_div as f32, j as f32 / num_div as f32);
+                let d = 1. / num_div as f32;
+                tris.push([[x, y], [x + d, y], [x + d * 0.3, y + d]]);
+            }
+        }
+        let mut img_data = vec![0u8; img_size.0 * img_size.1];
+        let t0 = measure("triangle2::fill_per_pixel", 20, || {
+            for tri in tris.iter() {
+                del_canvas::rasterize::triangle2::fill_per_pixel(
+                    &mut img_data,
+                    img_size.0,
+                    &tri[0],
+                    &tri[1],
+                    &tri[2],
+                    &trans_world2pix,
+                    1u8,
+                );
+            }
+        });
+        let t1 = measure("triangle2::fill_incremental", 20, || {
+            for tri in tris.iter() {
+                del_canvas::rasterize::triangle2::fill_incremental(
+                    &mut img_data,
+                    img_size.0,
+                    &tri[0],
+                    &tri[1],
+                    &tri[2],
+                    &trans_world2pix,
+                    1u8,
+                );
+            }
+        });
+        println!("speedup: {:.1}x", t0.as_secs_f64() / t1.as_secs_f64());
+        std::hint::black_box(&img_data);
+    }
+}
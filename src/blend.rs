@@ -0,0 +1,214 @@
+//! compositing the color of a rasterized primitive into the pixel.
+//!
+//! The `*_blend` functions in the `rasterize` module take `blend: &impl Blend<VAL>`.
+//! The other rasterize functions simply overwrite the pixel using `Overwrite`.
+
+/// how a color `src` is composited into the pixel value `dst`
+pub trait Blend<VAL> {
+    /// * `coverage` - fraction of the pixel covered by the primitive in `[0, 1]`
+    fn blend(&self, dst: &mut VAL, src: VAL, coverage: f32);
+}
+
+/// overwrite the pixel irrespective of the coverage.
+/// This works for any value type (e.g., palette or triangle indices).
+#[derive(Debug, Clone, Copy)]
+pub struct Overwrite;
+
+impl<VAL> Blend<VAL> for Overwrite
+where
+    VAL: Copy,
+{
+    fn blend(&self, dst: &mut VAL, src: VAL, _coverage: f32) {
+        *dst = src;
+    }
+}
+
+/// custom blending with a closure `|dst, src, coverage| { ... }`
+impl<VAL, F> Blend<VAL> for F
+where
+    F: Fn(&mut VAL, VAL, f32),
+{
+    fn blend(&self, dst: &mut VAL, src: VAL, coverage: f32) {
+        self(dst, src, coverage)
+    }
+}
+
+/// the blending modes for pixel types implementing `Pixel`.
+/// The result is linearly interpolated with the original pixel value by the coverage,
+/// so the modes also work for anti-aliased primitives.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BlendMode {
+    /// `src`
+    Overwrite,
+    /// "over" compositing of the non-premultiplied colors where `alpha` is the alpha of `src`
+    /// times the coverage. The alpha becomes `out_alpha = alpha + dst_alpha * (1 - alpha)` and
+    /// the color `(src * alpha + dst * dst_alpha * (1 - alpha)) / out_alpha`.
+    /// Without the alpha channel, `src * coverage + dst * (1 - coverage)`
+    AlphaOver,
+    /// `dst + src`, saturated for integer channels
+    Add,
+    /// `min(dst, src)`
+    Min,
+    /// `max(dst, src)` e.g., for the maximum intensity projection
+    Max,
+    /// `dst * src` with normalized channels
+    Multiply,
+}
+
+impl<VAL> Blend<VAL> for BlendMode
+where
+    VAL: Pixel,
+{
+    fn blend(&self, dst: &mut VAL, src: VAL, coverage: f32) {
+        let coverage = coverage as f64;
+        if let (BlendMode::AlphaOver, Some(i_alpha)) = (self, VAL::ALPHA) {
+            // "over" operator for the non-premultiplied colors
+            let a = src.channel(i_alpha) * coverage;
+            let da = dst.channel(i_alpha);
+            let out_a = a + da * (1. - a);
+            if out_a <= 0. {
+                return;
+            }
+            for i_ch in (0..VAL::NUM_CHANNEL).filter(|&i_ch| i_ch != i_alpha) {
+                let (d, s) = (dst.channel(i_ch), src.channel(i_ch));
+                dst.set_channel(i_ch, (s * a + d * da * (1. - a)) / out_a);
+            }
+            dst.set_channel(i_alpha, out_a);
+            return;
+        }
+        for i_ch in 0..VAL::NUM_CHANNEL {
+            let d = dst.channel(i_ch);
+            let s = src.channel(i_ch);
+            let v = match self {
+                BlendMode::Overwrite | BlendMode::AlphaOver => s,
+                BlendMode::Add => {
+                    dst.set_channel(i_ch, d + s * coverage);
+                    continue;
+                }
+                BlendMode::Min => d.min(s),
+                BlendMode::Max => d.max(s),
+                BlendMode::Multiply => d * s,
+            };
+            dst.set_channel(i_ch, d + (v - d) * coverage);
+        }
+    }
+}
+
+// ---------------------------
+
+/// a scalar channel of a pixel. Integer channels are normalized to `[0, 1]`
+pub trait Channel: Copy {
+    fn to_normalized(self) -> f64;
+    fn from_normalized(v: f64) -> Self;
+}
+
+impl Channel for f32 {
+    fn to_normalized(self) -> f64 {
+        self as f64
+    }
+    fn from_normalized(v: f64) -> Self {
+        v as f32
+    }
+}
+
+impl Channel for f64 {
+    fn to_normalized(self) -> f64 {
+        self
+    }
+    fn from_normalized(v: f64) -> Self {
+        v
+    }
+}
+
+impl Channel for u8 {
+    fn to_normalized(self) -> f64 {
+        self as f64 / 255.
+    }
+    fn from_normalized(v: f64) -> Self {
+        (v * 255.).round().clamp(0., 255.) as u8
+    }
+}
+
+impl Channel for u16 {
+    fn to_normalized(self) -> f64 {
+        self as f64 / 65535.
+    }
+    fn from_normalized(v: f64) -> Self {
+        (v * 65535.).round().clamp(0., 65535.) as u16
+    }
+}
+
+/// pixel value made of `NUM_CHANNEL` channels
+pub trait Pixel: Copy {
+    const NUM_CHANNEL: usize;
+    /// index of the alpha channel if exists
+    const ALPHA: Option<usize>;
+    fn channel(&self, i_channel: usize) -> f64;
+    fn set_channel(&mut self, i_channel: usize, v: f64);
+}
+
+impl<C> Pixel for C
+where
+    C: Channel,
+{
+    const NUM_CHANNEL: usize = 1;
+    const ALPHA: Option<usize> = None;
+    fn channel(&self, _i_channel: usize) -> f64 {
+        self.to_normalized()
+    }
+    fn set_channel(&mut self, _i_channel: usize, v: f64) {
+        *self = C::from_normalized(v);
+    }
+}
+
+/// the last channel is the alpha channel for the gray-alpha (`N=2`) and the RGBA (`N=4`) pixels
+impl<C, const N: usize> Pixel for [C; N]
+where
+    C: Channel,
+{
+    const NUM_CHANNEL: usize = N;
+    const ALPHA: Option<usize> = if N == 2 || N == 4 { Some(N - 1) } else { None };
+    fn channel(&self, i_channel: usize) -> f64 {
+        self[i_channel].to_normalized()
+    }
+    fn set_channel(&mut self, i_channel: usize, v: f64) {
+        self[i_channel] = C::from_normalized(v);
+    }
+}
+
+#[test]
+fn test_blend_mode() {
+    let mut v = [0u8, 0, 255];
+    BlendMode::AlphaOver.blend(&mut v, [255u8, 0, 0], 0.5);
+    assert_eq!(v, [128, 0, 128]);
+    //
+    let mut v = [0f32, 0., 1., 1.];
+    BlendMode::AlphaOver.blend(&mut v, [1f32, 0., 0., 0.5], 1.0);
+    assert_eq!(v, [0.5, 0., 0.5, 1.0]);
+    // the transparent destination does not darken the color
+    let mut v = [0u8, 0, 0, 0];
+    BlendMode::AlphaOver.blend(&mut v, [255u8, 0, 0, 128], 1.0);
+    assert_eq!(v, [255, 0, 0, 128]);
+    let mut v = [0u8, 0, 255, 255];
+    BlendMode::AlphaOver.blend(&mut v, [255u8, 0, 0, 255], 0.5);
+    assert_eq!(v, [128, 0, 128, 255]);
+    //
+    let mut v = 250u8;
+    BlendMode::Add.blend(&mut v, 10u8, 1.0);
+    assert_eq!(v, 255);
+    //
+    let mut v = 0.25f32;
+    BlendMode::Max.blend(&mut v, 0.75f32, 1.0);
+    assert_eq!(v, 0.75);
+    BlendMode::Min.blend(&mut v, 0.5f32, 1.0);
+    assert_eq!(v, 0.5);
+    BlendMode::Multiply.blend(&mut v, 0.5f32, 1.0);
+    assert_eq!(v, 0.25);
+    //
+    let mut v = 3usize;
+    Overwrite.blend(&mut v, 7usize, 0.1);
+    assert_eq!(v, 7);
+    let add = |dst: &mut usize, src: usize, _coverage: f32| *dst += src;
+    add.blend(&mut v, 2, 1.0);
+    assert_eq!(v, 9);
+}
@@ -0,0 +1,315 @@
+//! drawing API shared by the bitmap, GIF and SVG canvases.
+//!
+//! ```
+//! use del_canvas::canvas::{Draw, Style};
+//! use del_canvas::color::Rgba;
+//! // the same routine can draw into any canvas
+//! fn plot<D: Draw>(canvas: &mut D, transform_xy2pix: &[f32; 9]) {
+//!     canvas.clear(Rgba::WHITE);
+//!     let style = Style { fill: Some(Rgba::from(0xff0000)), ..Default::default() };
+//!     canvas.triangle(&[0.1, 0.1], &[0.9, 0.1], &[0.5, 0.9], transform_xy2pix, &style);
+//!     canvas.circle(&[0.5, 0.5], 3.0, transform_xy2pix, &Style::default());
+//! }
+//! let transform_xy2pix = [100f32, 0., 0., 0., -100., 0., 0., 100., 1.];
+//! let mut bitmap = del_canvas::canvas_bitmap::Canvas::new((100, 100));
+//! plot(&mut bitmap, &transform_xy2pix);
+//! let mut svg = del_canvas::canvas_svg::Canvas::new("plot.svg".to_string(), (100, 100));
+//! plot(&mut svg, &transform_xy2pix);
+//! ```
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LineCap {
+    Butt,
+    Round,
+    Square,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LineJoin {
+    Miter,
+    Round,
+    Bevel,
+}
+
+use crate::color::Rgba;
+
+/// appearance of the primitives drawn by `Draw`. The default is the black stroke without fill.
+/// The dash pattern, line caps, line joins and arrows are only reflected in the SVG output.
+/// ```
+/// let style = del_canvas::canvas::Style {
+///     fill: Some(0xff0000.into()),
+///     dash: vec![4., 2.],
+///     ..Default::default()
+/// };
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Style {
+    /// stroke color. `None` for `stroke="none"`
+    pub stroke: Option<Rgba<u8>>,
+    /// stroke width in the pixel coordinate
+    pub stroke_width: Option<f32>,
+    /// fill color. `None` for `fill="none"`
+    pub fill: Option<Rgba<u8>>,
+    /// multiplied to the alpha of the colors
+    pub opacity: Option<f32>,
+    /// lengths of the dashes and gaps in the pixel coordinate. Solid line if empty
+    pub dash: Vec<f32>,
+    pub line_cap: Option<LineCap>,
+    pub line_join: Option<LineJoin>,
+    /// put an arrow head at the start of the line, polyline or path
+    pub arrow_start: bool,
+    /// put an arrow head at the end of the line, polyline or path
+    pub arrow_end: bool,
+}
+
+impl Default for Style {
+    fn default() -> Self {
+        Style {
+            stroke: Some(Rgba::BLACK),
+            stroke_width: None,
+            fill: None,
+            opacity: None,
+            dash: vec![],
+            line_cap: None,
+            line_join: None,
+            arrow_start: false,
+            arrow_end: false,
+        }
+    }
+}
+
+/// drawing primitives in the `xy` coordinate
+/// * `transform_xy2pix` - 3x3 homogeneous transformation matrix with **column major** order
+pub trait Draw {
+    /// fill the whole canvas with the color ignoring its alpha
+    fn clear(&mut self, color: Rgba<u8>);
+
+    fn line(&mut self, p0: &[f32; 2], p1: &[f32; 2], transform_xy2pix: &[f32; 9], style: &Style);
+
+    /// open polyline. `vtx2xy` is stored as `[x0, y0, x1, y1, ...]`
+    fn polyline(&mut self, vtx2xy: &[f32], transform_xy2pix: &[f32; 9], style: &Style) {
+        for i_vtx in 0..(vtx2xy.len() / 2).saturating_sub(1) {
+            self.line(
+                arrayref::array_ref![vtx2xy, i_vtx * 2, 2],
+                arrayref::array_ref![vtx2xy, i_vtx * 2 + 2, 2],
+                transform_xy2pix,
+                style,
+            );
+        }
+    }
+
+    /// closed polygon. `vtx2xy` is stored as `[x0, y0, x1, y1, ...]`
+    fn polygon(&mut self, vtx2xy: &[f32], transform_xy2pix: &[f32; 9], style: &Style);
+
+    /// * `radius_pix` - radius in the pixel coordinate
+    fn circle(
+        &mut self,
+        center: &[f32; 2],
+        radius_pix: f32,
+        transform_xy2pix: &[f32; 9],
+        style: &Style,
+    );
+
+    fn triangle(
+        &mut self,
+        p0: &[f32; 2],
+        p1: &[f32; 2],
+        p2: &[f32; 2],
+        transform_xy2pix: &[f32; 9],
+        style: &Style,
+    ) {
+        let vtx2xy = [p0[0], p0[1], p1[0], p1[1], p2[0], p2[1]];
+        self.polygon(&vtx2xy, transform_xy2pix, style);
+    }
+}
+
+// ---------------------------
+// rasterization shared by the bitmap and GIF canvases
+
+/// scale the coverage by the opacity of the color
+struct WithOpacity<'a, BLEND>(&'a BLEND, f32);
+
+impl<VAL, BLEND> crate::blend::Blend<VAL> for WithOpacity<'_, BLEND>
+where
+    BLEND: crate::blend::Blend<VAL>,
+{
+    fn blend(&self, dst: &mut VAL, src: VAL, coverage: f32) {
+        self.0.blend(dst, src, coverage * self.1)
+    }
+}
+
+fn opacity(style: &Style, color: &Rgba<u8>) -> f32 {
+    style.opacity.unwrap_or(1.) * color.opacity()
+}
+
+/// stroke the polyline with `style.stroke` and the thickness of `style.stroke_width`
+/// (one pixel if not specified)
+/// * `to_val` - conversion from the color to the pixel value
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn stroke_polyline<VAL, BLEND>(
+    img_data: &mut [VAL],
+    width: usize,
+    vtx2xy: &[f32],
+    is_closed: bool,
+    transform_xy2pix: &[f32; 9],
+    style: &Style,
+    to_val: &impl Fn(Rgba<u8>) -> VAL,
+    blend: &BLEND,
+) where
+    VAL: Copy,
+    BLEND: crate::blend::Blend<VAL>,
+{
+    let Some(stroke) = style.stroke else {
+        return;
+    };
+    let num_vtx = vtx2xy.len() / 2;
+    if num_vtx < 2 {
+        return;
+    }
+    let num_edge = if is_closed { num_vtx } else { num_vtx - 1 };
+    let thickness = 0.5 * style.stroke_width.unwrap_or(1.);
+    let blend = WithOpacity(blend, opacity(style, &stroke));
+    for i_edge in 0..num_edge {
+        let j_vtx = (i_edge + 1) % num_vtx;
+        crate::rasterize::line2::draw_pixcenter_antialias(
+            img_data,
+            width,
+            arrayref::array_ref![vtx2xy, i_edge * 2, 2],
+            arrayref::array_ref![vtx2xy, j_vtx * 2, 2],
+            transform_xy2pix,
+            thickness,
+            to_val(stroke),
+            &blend,
+        );
+    }
+}
+
+/// vertices of the circle in the pixel coordinate with the edge length about two pixels
+pub(crate) fn circle_vtx2xy_pix(
+    center: &[f32; 2],
+    radius_pix: f32,
+    transform_xy2pix: &[f32; 9],
+) -> Vec<f32> {
+    let c = del_geo_core::mat3_col_major::transform_homogeneous(transform_xy2pix, center).unwrap();
+    let num_vtx = ((std::f32::consts::PI * radius_pix).ceil() as usize).max(8);
+    (0..num_vtx)
+        .flat_map(|i_vtx| {
+            let theta = 2. * std::f32::consts::PI * i_vtx as f32 / num_vtx as f32;
+            [
+                c[0] + radius_pix * theta.cos(),
+                c[1] + radius_pix * theta.sin(),
+            ]
+        })
+        .collect()
+}
+
+/// fill and then stroke the polygon
+/// * `to_val` - conversion from the color to the pixel value
+pub(crate) fn draw_polygon<VAL, BLEND>(
+    img_data: &mut [VAL],
+    width: usize,
+    vtx2xy: &[f32],
+    transform_xy2pix: &[f32; 9],
+    style: &Style,
+    to_val: &impl Fn(Rgba<u8>) -> VAL,
+    blend: &BLEND,
+) where
+    VAL: Copy,
+    BLEND: crate::blend::Blend<VAL>,
+{
+    if let Some(fill) = style.fill {
+        crate::rasterize::polygon2::fill_loops_blend(
+            img_data,
+            width,
+            &[vtx2xy],
+            transform_xy2pix,
+            crate::rasterize::polygon2::FillRule::NonZero,
+            to_val(fill),
+            &WithOpacity(blend, opacity(style, &fill)),
+        );
+    }
+    stroke_polyline(
+        img_data,
+        width,
+        vtx2xy,
+        true,
+        transform_xy2pix,
+        style,
+        to_val,
+        blend,
+    );
+}
+
+#[test]
+fn test_draw() -> anyhow::Result<()> {
+    fn plot<D: Draw>(canvas: &mut D, transform_xy2pix: &[f32; 9]) {
+        canvas.clear(Rgba::WHITE);
+        let style_fill = Style {
+            fill: Some(Rgba::from(0xff0000)),
+            stroke: None,
+            ..Default::default()
+        };
+        canvas.triangle(
+            &[0.1, 0.1],
+            &[0.9, 0.1],
+            &[0.5, 0.5],
+            transform_xy2pix,
+            &style_fill,
+        );
+        let style_blue = Style {
+            stroke: Some(Rgba::from(0x0000ff)),
+            stroke_width: Some(3.),
+            ..Default::default()
+        };
+        canvas.polyline(
+            &[0.1, 0.9, 0.5, 0.7, 0.9, 0.9],
+            transform_xy2pix,
+            &style_blue,
+        );
+        canvas.circle(&[0.5, 0.6], 5., transform_xy2pix, &style_fill);
+        canvas.line(
+            &[0.0, 0.0],
+            &[1.0, 1.0],
+            transform_xy2pix,
+            &Style::default(),
+        );
+    }
+    let img_size = (64usize, 64usize);
+    let transform_xy2pix =
+        crate::cam2::transform_world2pix_ortho_preserve_asp(&img_size, &[0.0, 0.0, 1.0, 1.0]);
+    let to_pix = |x: f32, y: f32| {
+        let p = del_geo_core::mat3_col_major::transform_homogeneous(&transform_xy2pix, &[x, y])
+            .unwrap();
+        p[1] as usize * img_size.0 + p[0] as usize
+    };
+    {
+        let mut canvas = crate::canvas_bitmap::Canvas::new(img_size);
+        plot(&mut canvas, &transform_xy2pix);
+        let pix = |i_pix: usize| &canvas.data[i_pix * 3..i_pix * 3 + 3];
+        assert_eq!(pix(to_pix(0.5, 0.2)), [255, 0, 0]);
+        assert_eq!(pix(to_pix(0.5, 0.6)), [255, 0, 0]);
+        assert_eq!(pix(to_pix(0.9, 0.1)), [255, 255, 255]);
+        assert_eq!(pix(to_pix(0.3, 0.8)), [0, 0, 255]);
+        canvas.write("target/canvas_draw.png")?;
+    }
+    {
+        let palette = [0xffffff, 0x000000, 0xff0000, 0x0000ff];
+        let mut canvas =
+            crate::canvas_gif::Canvas::new("target/canvas_draw.gif", img_size, &palette)?;
+        plot(&mut canvas, &transform_xy2pix);
+        assert_eq!(canvas.data[to_pix(0.5, 0.2)], 2);
+        assert_eq!(canvas.data[to_pix(0.9, 0.1)], 0);
+        assert_eq!(canvas.data[to_pix(0.3, 0.8)], 3);
+        canvas.write()?;
+        canvas.finish()?;
+    }
+    {
+        let mut canvas =
+            crate::canvas_svg::Canvas::new("target/canvas_draw.svg".to_string(), img_size);
+        plot(&mut canvas, &transform_xy2pix);
+        assert_eq!(canvas.tags.len(), 5);
+        assert!(canvas.tags[3].starts_with("<circle"));
+        canvas.write()?;
+    }
+    Ok(())
+}
@@ -0,0 +1,380 @@
+//! full-color RGBA animation written as APNG or as the numbered PNG files.
+//! Unlike `canvas_gif::Canvas`, the colors are not limited to a palette.
+
+/// destination of the frames
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Output {
+    /// single animated PNG file. Each frame is encoded to the file immediately;
+    /// the number of the frames in the header is written by `finish` (or on drop).
+    /// If the process dies before that, the file is broken: the header claims `2^31 - 1` frames
+    /// and the `IEND` chunk is missing
+    Apng(std::path::PathBuf),
+    /// `frame_00000.png`, `frame_00001.png`, ... and `manifest.json` in the directory.
+    /// Each frame is written immediately and the manifest is updated,
+    /// so the frames can be inspected while the simulation is running
+    PngSequence(std::path::PathBuf),
+}
+
+pub struct Canvas {
+    pub width: usize,
+    pub height: usize,
+    /// RGBA pixels `[r0, g0, b0, a0, r1, ...]`
+    pub data: Vec<u8>,
+    /// delay after the frame in milliseconds
+    pub delay_ms: u16,
+    /// number of the loops of the APNG animation. `0` for infinite.
+    /// It is written in the header, so set it before the first frame
+    pub num_plays: u32,
+    output: Output,
+    /// APNG encoder opened at the first frame
+    apng: Option<png::Writer<std::io::BufWriter<std::fs::File>>>,
+    /// file names (empty for APNG) and delays of the frames written so far
+    manifest: Vec<(String, u16)>,
+    is_finished: bool,
+}
+
+impl Canvas {
+    /// animated PNG file at `path`
+    pub fn new<Path: AsRef<std::path::Path>>(path: Path, size: (usize, usize)) -> Self {
+        Self::with_output(Output::Apng(path.as_ref().to_owned()), size)
+    }
+
+    /// numbered PNG files and the manifest in the directory `dir` (created if it does not exist)
+    pub fn new_png_sequence<Path: AsRef<std::path::Path>>(
+        dir: Path,
+        size: (usize, usize),
+    ) -> anyhow::Result<Self> {
+        std::fs::create_dir_all(dir.as_ref())?;
+        Ok(Self::with_output(
+            Output::PngSequence(dir.as_ref().to_owned()),
+            size,
+        ))
+    }
+
+    fn with_output(output: Output, size: (usize, usize)) -> Self {
+        Canvas {
+            width: size.0,
+            height: size.1,
+            data: vec![0; size.0 * size.1 * 4],
+            delay_ms: 100,
+            num_plays: 0,
+            output,
+            apng: None,
+            manifest: vec![],
+            is_finished: false,
+        }
+    }
+
+    pub fn output(&self) -> &Output {
+        &self.output
+    }
+
+    /// number of the frames written so far
+    pub fn num_frames(&self) -> usize {
+        self.manifest.len()
+    }
+
+    pub fn clear(&mut self, color: crate::color::Rgba<u8>) {
+        let rgba: [u8; 4] = color.into();
+        self.data
+            .chunks_exact_mut(4)
+            .for_each(|p| p.copy_from_slice(&rgba));
+    }
+
+    /// add the current `data` as a frame with `delay_ms`
+    pub fn write(&mut self) -> anyhow::Result<()> {
+        anyhow::ensure!(!self.is_finished, "the animation is already finished");
+        match &self.output {
+            Output::Apng(path) => {
+                if self.apng.is_none() {
+                    self.apng = Some(apng_writer(path, self.width, self.height, self.num_plays)?);
+                }
+                let writer = self.apng.as_mut().unwrap();
+                writer.set_frame_delay(self.delay_ms, 1000)?;
+                writer.write_image_data(&self.data)?;
+                self.manifest.push((String::new(), self.delay_ms));
+            }
+            Output::PngSequence(dir) => {
+                let file_name = format!("frame_{:05}.png", self.manifest.len());
+                image::save_buffer(
+                    dir.join(&file_name),
+                    &self.data,
+                    self.width as u32,
+                    self.height as u32,
+                    image::ExtendedColorType::Rgba8,
+                )?;
+                self.manifest.push((file_name, self.delay_ms));
+                self.write_manifest()?;
+            }
+        }
+        Ok(())
+    }
+
+    /// add the RGB image (e.g., `canvas_bitmap::Canvas::data`) as an opaque frame
+    pub fn write_rgb(&mut self, rgb: &[u8]) -> anyhow::Result<()> {
+        anyhow::ensure!(
+            rgb.len() == self.width * self.height * 3,
+            "the size of the RGB image does not match the canvas"
+        );
+        for (dst, src) in self.data.chunks_exact_mut(4).zip(rgb.chunks_exact(3)) {
+            dst.copy_from_slice(&[src[0], src[1], src[2], 255]);
+        }
+        self.write()
+    }
+
+    /// add the RGBA image as a frame
+    pub fn write_rgba(&mut self, rgba: &[u8]) -> anyhow::Result<()> {
+        anyhow::ensure!(
+            rgba.len() == self.data.len(),
+            "the size of the RGBA image does not match the canvas"
+        );
+        self.data.copy_from_slice(rgba);
+        self.write()
+    }
+
+    /// `manifest.json` listing the frames with their delays
+    fn write_manifest(&self) -> anyhow::Result<()> {
+        let Output::PngSequence(dir) = &self.output else {
+            return Ok(());
+        };
+        let frames: Vec<String> = self
+            .manifest
+            .iter()
+            .map(|(file_name, delay_ms)| {
+                format!(
+                    "    {{\"file\": \"{}\", \"delay_ms\": {}}}",
+                    file_name, delay_ms
+                )
+            })
+            .collect();
+        let json = format!(
+            "{{\n  \"width\": {},\n  \"height\": {},\n  \"num_frames\": {},\n  \"frames\": [\n{}\n  ]\n}}\n",
+            self.width,
+            self.height,
+            frames.len(),
+            frames.join(",\n")
+        );
+        // write to the temporary file first so that a reader never sees a half-written manifest
+        let path = dir.join("manifest.json");
+        let path_tmp = dir.join("manifest.json.tmp");
+        std::fs::write(&path_tmp, json)?;
+        std::fs::rename(path_tmp, path)?;
+        Ok(())
+    }
+
+    /// complete the APNG file (or finalize the manifest for the PNG sequence).
+    /// No frame can be added after this. It is also called when the canvas is dropped,
+    /// but call it explicitly to get the error
+    pub fn finish(&mut self) -> anyhow::Result<()> {
+        if self.is_finished {
+            return Ok(());
+        }
+        self.is_finished = true;
+        let path = match &self.output {
+            Output::Apng(path) => path,
+            Output::PngSequence(_) => return self.write_manifest(),
+        };
+        let Some(writer) = self.apng.take() else {
+            anyhow::bail!("no frame is written");
+        };
+        writer.finish()?;
+        patch_apng_num_frames(path, self.manifest.len() as u32)
+    }
+}
+
+impl Drop for Canvas {
+    fn drop(&mut self) {
+        let _ = self.finish();
+    }
+}
+
+/// placeholder of the number of the frames in the `acTL` chunk until `finish` patches it
+const NUM_FRAMES_UNKNOWN: u32 = (1 << 31) - 1;
+
+fn apng_writer(
+    path: &std::path::Path,
+    width: usize,
+    height: usize,
+    num_plays: u32,
+) -> anyhow::Result<png::Writer<std::io::BufWriter<std::fs::File>>> {
+    let file = std::fs::File::create(path)?;
+    let mut encoder = png::Encoder::new(std::io::BufWriter::new(file), width as u32, height as u32);
+    encoder.set_color(png::ColorType::Rgba);
+    encoder.set_depth(png::BitDepth::Eight);
+    encoder.set_animated(NUM_FRAMES_UNKNOWN, num_plays)?;
+    // the frames are fewer than the placeholder
+    encoder.validate_sequence(false);
+    Ok(encoder.write_header()?)
+}
+
+/// overwrite the number of the frames (and the CRC) of the `acTL` chunk
+fn patch_apng_num_frames(path: &std::path::Path, num_frames: u32) -> anyhow::Result<()> {
+    use std::io::{Read, Seek, SeekFrom, Write};
+    let mut file = std::fs::OpenOptions::new()
+        .read(true)
+        .write(true)
+        .open(path)?;
+    let file_len = file.metadata()?.len();
+    // each chunk after the 8-byte signature is length (4), type (4), data and CRC (4)
+    let mut pos = 8u64;
+    loop {
+        anyhow::ensure!(pos + 8 <= file_len, "the acTL chunk is not found");
+        let mut head = [0u8; 8];
+        file.seek(SeekFrom::Start(pos))?;
+        file.read_exact(&mut head)?;
+        let (len, chunk_type) = (
+            u32::from_be_bytes(*arrayref::array_ref![head, 0, 4]),
+            &head[4..],
+        );
+        if chunk_type == b"acTL" {
+            anyhow::ensure!(len == 8, "the acTL chunk is broken");
+            let mut data = [0u8; 8];
+            file.read_exact(&mut data)?;
+            data[..4].copy_from_slice(&num_frames.to_be_bytes());
+            let mut crc = crc32fast::Hasher::new();
+            crc.update(b"acTL");
+            crc.update(&data);
+            file.seek(SeekFrom::Start(pos + 8))?;
+            file.write_all(&data)?;
+            file.write_all(&crc.finalize().to_be_bytes())?;
+            return Ok(());
+        }
+        anyhow::ensure!(chunk_type != b"IDAT", "the acTL chunk is not found");
+        pos += 12 + len as u64;
+    }
+}
+
+impl crate::canvas::Draw for Canvas {
+    fn clear(&mut self, color: crate::color::Rgba<u8>) {
+        Canvas::clear(self, color);
+    }
+
+    fn line(
+        &mut self,
+        p0: &[f32; 2],
+        p1: &[f32; 2],
+        transform_xy2pix: &[f32; 9],
+        style: &crate::canvas::Style,
+    ) {
+        use slice_of_array::SliceNestExt;
+        crate::canvas::stroke_polyline(
+            self.data.nest_mut(),
+            self.width,
+            &[p0[0], p0[1], p1[0], p1[1]],
+            false,
+            transform_xy2pix,
+            style,
+            &opaque,
+            &crate::blend::BlendMode::AlphaOver,
+        );
+    }
+
+    fn polygon(
+        &mut self,
+        vtx2xy: &[f32],
+        transform_xy2pix: &[f32; 9],
+        style: &crate::canvas::Style,
+    ) {
+        use slice_of_array::SliceNestExt;
+        crate::canvas::draw_polygon(
+            self.data.nest_mut(),
+            self.width,
+            vtx2xy,
+            transform_xy2pix,
+            style,
+            &opaque,
+            &crate::blend::BlendMode::AlphaOver,
+        );
+    }
+
+    fn circle(
+        &mut self,
+        center: &[f32; 2],
+        radius_pix: f32,
+        transform_xy2pix: &[f32; 9],
+        style: &crate::canvas::Style,
+    ) {
+        let vtx2xy = crate::canvas::circle_vtx2xy_pix(center, radius_pix, transform_xy2pix);
+        self.polygon(&vtx2xy, &[1., 0., 0., 0., 1., 0., 0., 0., 1.], style);
+    }
+}
+
+/// the alpha of the color is already multiplied to the coverage by the `canvas` helpers
+fn opaque(c: crate::color::Rgba<u8>) -> [u8; 4] {
+    [c.r, c.g, c.b, 255]
+}
+
+#[test]
+fn test_apng() -> anyhow::Result<()> {
+    use crate::canvas::Draw;
+    let img_size = (48, 32);
+    let transform_xy2pix = [1., 0., 0., 0., 1., 0., 0., 0., 1.];
+    let style = crate::canvas::Style {
+        fill: Some(crate::color::Rgba::new(255, 0, 0, 128)),
+        ..Default::default()
+    };
+    let path = "target/canvas_apng.png";
+    let mut canvas = Canvas::new(path, img_size);
+    canvas.num_plays = 2;
+    for i_frame in 0..3 {
+        canvas.delay_ms = 50 * (i_frame + 1);
+        canvas.clear(crate::color::Rgba::TRANSPARENT);
+        Draw::circle(
+            &mut canvas,
+            &[10. + 12. * i_frame as f32, 16.],
+            8.,
+            &transform_xy2pix,
+            &style,
+        );
+        canvas.write()?;
+    }
+    // semi-transparent red over the transparent background
+    let center = (16 * img_size.0 + 34) * 4;
+    assert_eq!(&canvas.data[center..center + 4], &[255, 0, 0, 128]);
+    canvas.finish()?;
+    assert!(canvas.write().is_err());
+    //
+    let decoder = png::Decoder::new(std::io::BufReader::new(std::fs::File::open(path)?));
+    let mut reader = decoder.read_info()?;
+    let animation = reader.info().animation_control.unwrap();
+    assert_eq!((animation.num_frames, animation.num_plays), (3, 2));
+    let mut buf = vec![0; reader.output_buffer_size().unwrap()];
+    for i_frame in 0..3u16 {
+        reader.next_frame(&mut buf)?;
+        let fc = reader.info().frame_control.unwrap();
+        assert_eq!((fc.delay_num, fc.delay_den), (50 * (i_frame + 1), 1000));
+    }
+    assert_eq!(&buf[center..center + 4], &[255, 0, 0, 128]);
+    // dropping the canvas finishes the file
+    {
+        let mut canvas = Canvas::new("target/canvas_apng_drop.png", img_size);
+        canvas.write()?;
+        canvas.write()?;
+    }
+    let decoder = png::Decoder::new(std::io::BufReader::new(std::fs::File::open(
+        "target/canvas_apng_drop.png",
+    )?));
+    let reader = decoder.read_info()?;
+    assert_eq!(reader.info().animation_control.unwrap().num_frames, 2);
+    // the scan stops at the end of the file or at the image data
+    let path_still = "target/canvas_apng_still.png";
+    image::save_buffer(path_still, &[0; 16], 2, 2, image::ExtendedColorType::Rgba8)?;
+    assert!(patch_apng_num_frames(path_still.as_ref(), 1).is_err());
+    std::fs::write(path_still, b"\x89PNG\r\n\x1a\n")?;
+    assert!(patch_apng_num_frames(path_still.as_ref(), 1).is_err());
+    //
+    let dir = "target/canvas_apng_sequence";
+    let mut canvas = Canvas::new_png_sequence(dir, img_size)?;
+    let rgb = vec![128u8; img_size.0 * img_size.1 * 3];
+    canvas.write_rgb(&rgb)?;
+    canvas.delay_ms = 40;
+    canvas.write_rgb(&rgb)?;
+    assert!(canvas.write_rgb(&rgb[3..]).is_err());
+    canvas.finish()?;
+    let manifest = std::fs::read_to_string(format!("{}/manifest.json", dir))?;
+    assert!(manifest.contains("\"num_frames\": 2"));
+    assert!(manifest.contains("{\"file\": \"frame_00001.png\", \"delay_ms\": 40}"));
+    let img = image::open(format!("{}/frame_00001.png", dir))?.to_rgba8();
+    assert_eq!(img.get_pixel(0, 0).0, [128, 128, 128, 255]);
+    Ok(())
+}
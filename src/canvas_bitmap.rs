@@ -1,22 +1,449 @@
-pub struct Canvas {
+//! image container of `N` interleaved channels of the sample type `T` (e.g., `u8`, `u16` or `f32`).
+//! The default `Canvas` is the 8-bit RGB image.
+//!
+//! ```
+//! use del_canvas::canvas_bitmap::Canvas;
+//! let mut img = Canvas::<f32, 1>::filled((4, 3), [0.5]);
+//! img.pixel_mut(1, 2)[0] = 1.0;
+//! let rgb: Canvas<u8, 3> = img.to_channels::<3>().convert::<u8>();
+//! assert_eq!(rgb.pixel(1, 2), &[255, 255, 255]);
+//! assert_eq!(rgb.pixel(0, 0), &[128, 128, 128]);
+//! ```
+
+use crate::blend::Channel;
+use slice_of_array::{SliceFlatExt, SliceNestExt};
+
+pub struct Canvas<T = u8, const N: usize = 3> {
     pub width: usize,
     pub height: usize,
-    pub data: Vec<u8>,
+    /// pixels in the row-major order with the interleaved channels `[c0, c1, ..., c0, c1, ...]`
+    pub data: Vec<T>,
 }
 
 impl Canvas {
+    /// black 8-bit RGB image
     pub fn new(size: (usize, usize)) -> Self {
-        Self {
+        Self::filled(size, [0; 3])
+    }
+}
+
+impl<T, const N: usize> Canvas<T, N>
+where
+    T: Channel,
+{
+    pub fn filled(size: (usize, usize), pixel: [T; N]) -> Self {
+        let mut data = Vec::with_capacity(size.0 * size.1 * N);
+        for _ in 0..size.0 * size.1 {
+            data.extend_from_slice(&pixel);
+        }
+        Canvas {
             width: size.0,
             height: size.1,
-            data: vec![0; size.0 * size.1 * 3],
+            data,
+        }
+    }
+
+    /// * `data` - `width * height * N` samples
+    pub fn from_vec(size: (usize, usize), data: Vec<T>) -> anyhow::Result<Self> {
+        anyhow::ensure!(
+            data.len() == size.0 * size.1 * N,
+            "the length of the data {} does not match the size {}x{}x{}",
+            data.len(),
+            size.0,
+            size.1,
+            N
+        );
+        Ok(Canvas {
+            width: size.0,
+            height: size.1,
+            data,
+        })
+    }
+
+    pub fn size(&self) -> (usize, usize) {
+        (self.width, self.height)
+    }
+
+    pub fn pixels(&self) -> &[[T; N]] {
+        self.data.nest()
+    }
+
+    pub fn pixels_mut(&mut self) -> &mut [[T; N]] {
+        self.data.nest_mut()
+    }
+
+    /// panics if `(x, y)` is outside the image
+    pub fn pixel(&self, x: usize, y: usize) -> &[T; N] {
+        assert!(x < self.width && y < self.height);
+        &self.pixels()[y * self.width + x]
+    }
+
+    /// panics if `(x, y)` is outside the image
+    pub fn pixel_mut(&mut self, x: usize, y: usize) -> &mut [T; N] {
+        assert!(x < self.width && y < self.height);
+        let width = self.width;
+        &mut self.pixels_mut()[y * width + x]
+    }
+
+    /// `None` if `(x, y)` is outside the image
+    pub fn get_pixel(&self, x: usize, y: usize) -> Option<&[T; N]> {
+        (x < self.width && y < self.height).then(|| &self.pixels()[y * self.width + x])
+    }
+
+    pub fn row(&self, y: usize) -> &[[T; N]] {
+        &self.pixels()[y * self.width..(y + 1) * self.width]
+    }
+
+    pub fn row_mut(&mut self, y: usize) -> &mut [[T; N]] {
+        let width = self.width;
+        &mut self.pixels_mut()[y * width..(y + 1) * width]
+    }
+
+    pub fn fill(&mut self, pixel: [T; N]) {
+        self.pixels_mut().fill(pixel);
+    }
+
+    /// sub-image view without copying
+    /// * `aabb` - `[x_min, y_min, x_max, y_max]` in pixel (exclusive max), clipped by the image
+    pub fn view(&self, aabb: [usize; 4]) -> View<'_, T, N> {
+        let (x0, y0, x1, y1) = clip(aabb, self.width, self.height);
+        View {
+            width: x1 - x0,
+            height: y1 - y0,
+            stride: self.width,
+            pixels: &self.pixels()[(y0 * self.width + x0).min(self.width * self.height)..],
+        }
+    }
+
+    /// mutable sub-image view without copying
+    /// * `aabb` - `[x_min, y_min, x_max, y_max]` in pixel (exclusive max), clipped by the image
+    pub fn view_mut(&mut self, aabb: [usize; 4]) -> ViewMut<'_, T, N> {
+        let (x0, y0, x1, y1) = clip(aabb, self.width, self.height);
+        let (width, height) = (self.width, self.height);
+        ViewMut {
+            width: x1 - x0,
+            height: y1 - y0,
+            stride: width,
+            pixels: &mut self.pixels_mut()[(y0 * width + x0).min(width * height)..],
+        }
+    }
+
+    /// change the sample type (e.g., `f32` in `[0, 1]` to `u8` in `[0, 255]`).
+    /// The values are clamped for the integer types
+    pub fn convert<U: Channel>(&self) -> Canvas<U, N> {
+        Canvas {
+            width: self.width,
+            height: self.height,
+            data: self
+                .data
+                .iter()
+                .map(|v| U::from_normalized(v.to_normalized()))
+                .collect(),
+        }
+    }
+
+    /// change the number of the channels among the gray (1), gray-alpha (2), RGB (3) and RGBA (4).
+    /// The gray is the Rec. 709 luma of RGB and the missing alpha is opaque
+    pub fn to_channels<const M: usize>(&self) -> Canvas<T, M> {
+        assert!((1..=4).contains(&N) && (1..=4).contains(&M));
+        self.map(|p| {
+            let v = convert_channels::<N, M>(&p.map(|v| v.to_normalized()));
+            v.map(|v| T::from_normalized(v))
+        })
+    }
+
+    /// new image made by applying `f` to every pixel
+    pub fn map<U, const M: usize>(&self, f: impl Fn(&[T; N]) -> [U; M]) -> Canvas<U, M> {
+        Canvas {
+            width: self.width,
+            height: self.height,
+            data: self.pixels().iter().flat_map(f).collect(),
+        }
+    }
+}
+
+/// normalized channel values converted between the gray, gray-alpha, RGB and RGBA
+fn convert_channels<const N: usize, const M: usize>(v: &[f64; N]) -> [f64; M] {
+    let rgba = match N {
+        1 => [v[0], v[0], v[0], 1.],
+        2 => [v[0], v[0], v[0], v[1]],
+        3 => [v[0], v[1], v[2], 1.],
+        _ => [v[0], v[1], v[2], v[3]],
+    };
+    let gray = 0.2126 * rgba[0] + 0.7152 * rgba[1] + 0.0722 * rgba[2];
+    let w = match M {
+        1 => [gray, 0., 0., 0.],
+        2 => [gray, rgba[3], 0., 0.],
+        _ => rgba,
+    };
+    std::array::from_fn(|i| w[i])
+}
+
+fn clip(aabb: [usize; 4], width: usize, height: usize) -> (usize, usize, usize, usize) {
+    let x1 = aabb[2].min(width);
+    let y1 = aabb[3].min(height);
+    (aabb[0].min(x1), aabb[1].min(y1), x1, y1)
+}
+
+/// rectangular region of a `Canvas`
+pub struct View<'a, T, const N: usize> {
+    pub width: usize,
+    pub height: usize,
+    /// number of the pixels between the starts of the rows
+    stride: usize,
+    /// pixels starting from the top-left corner of the view
+    pixels: &'a [[T; N]],
+}
+
+impl<T, const N: usize> View<'_, T, N>
+where
+    T: Channel,
+{
+    pub fn row(&self, y: usize) -> &[[T; N]] {
+        assert!(y < self.height);
+        &self.pixels[y * self.stride..y * self.stride + self.width]
+    }
+
+    pub fn pixel(&self, x: usize, y: usize) -> &[T; N] {
+        assert!(x < self.width);
+        &self.row(y)[x]
+    }
+
+    /// copy the region into a new image
+    pub fn to_canvas(&self) -> Canvas<T, N> {
+        Canvas {
+            width: self.width,
+            height: self.height,
+            data: (0..self.height)
+                .flat_map(|y| self.row(y).flat().iter().copied())
+                .collect(),
+        }
+    }
+}
+
+/// mutable rectangular region of a `Canvas`
+pub struct ViewMut<'a, T, const N: usize> {
+    pub width: usize,
+    pub height: usize,
+    /// number of the pixels between the starts of the rows
+    stride: usize,
+    /// pixels starting from the top-left corner of the view
+    pixels: &'a mut [[T; N]],
+}
+
+impl<T, const N: usize> ViewMut<'_, T, N>
+where
+    T: Channel,
+{
+    pub fn row_mut(&mut self, y: usize) -> &mut [[T; N]] {
+        assert!(y < self.height);
+        &mut self.pixels[y * self.stride..y * self.stride + self.width]
+    }
+
+    pub fn pixel_mut(&mut self, x: usize, y: usize) -> &mut [T; N] {
+        assert!(x < self.width);
+        &mut self.row_mut(y)[x]
+    }
+
+    pub fn fill(&mut self, pixel: [T; N]) {
+        (0..self.height).for_each(|y| self.row_mut(y).fill(pixel));
+    }
+
+    /// paste the image at the top-left corner of the view. The outside of the view is ignored
+    pub fn copy_from(&mut self, src: &Canvas<T, N>) {
+        let width = self.width.min(src.width);
+        for y in 0..self.height.min(src.height) {
+            self.row_mut(y)[..width].copy_from_slice(&src.row(y)[..width]);
         }
     }
+}
+
+// ---------------------------
+// saving the image without copying the buffer. The format is given by the extension.
+// PNG supports `u8` and `u16`, and HDR supports `Canvas<f32, 3>`.
+
+fn save<P, Q>(path: Q, width: usize, height: usize, data: &[P::Subpixel]) -> anyhow::Result<()>
+where
+    P: image::PixelWithColorType,
+    [P::Subpixel]: image::EncodableLayout,
+    Q: AsRef<std::path::Path>,
+{
+    let buffer =
+        image::ImageBuffer::<P, &[P::Subpixel]>::from_raw(width as u32, height as u32, data)
+            .ok_or_else(|| anyhow::anyhow!("the buffer is smaller than the image"))?;
+    Ok(buffer.save(path)?)
+}
+
+impl<T> Canvas<T, 1>
+where
+    image::Luma<T>: image::PixelWithColorType<Subpixel = T>,
+    [T]: image::EncodableLayout,
+{
+    pub fn write<P: AsRef<std::path::Path>>(&self, path: P) -> anyhow::Result<()> {
+        save::<image::Luma<T>, P>(path, self.width, self.height, &self.data)
+    }
+}
+
+impl<T> Canvas<T, 2>
+where
+    image::LumaA<T>: image::PixelWithColorType<Subpixel = T>,
+    [T]: image::EncodableLayout,
+{
+    pub fn write<P: AsRef<std::path::Path>>(&self, path: P) -> anyhow::Result<()> {
+        save::<image::LumaA<T>, P>(path, self.width, self.height, &self.data)
+    }
+}
+
+impl<T> Canvas<T, 3>
+where
+    image::Rgb<T>: image::PixelWithColorType<Subpixel = T>,
+    [T]: image::EncodableLayout,
+{
+    pub fn write<P: AsRef<std::path::Path>>(&self, path: P) -> anyhow::Result<()> {
+        save::<image::Rgb<T>, P>(path, self.width, self.height, &self.data)
+    }
+}
+
+impl<T> Canvas<T, 4>
+where
+    image::Rgba<T>: image::PixelWithColorType<Subpixel = T>,
+    [T]: image::EncodableLayout,
+{
+    pub fn write<P: AsRef<std::path::Path>>(&self, path: P) -> anyhow::Result<()> {
+        save::<image::Rgba<T>, P>(path, self.width, self.height, &self.data)
+    }
+}
+
+// ---------------------------
+
+/// the color in the `N` channels. See `Canvas::to_channels` for the conversion
+fn pixel_from_color<T: Channel, const N: usize>(c: crate::color::Rgba<u8>) -> [T; N] {
+    let rgba: [u8; 4] = c.into();
+    convert_channels::<4, N>(&rgba.map(|v| v.to_normalized())).map(T::from_normalized)
+}
+
+/// the opaque color because the alpha is already multiplied to the coverage by the `canvas` helpers
+fn pixel_from_opaque_color<T: Channel, const N: usize>(c: crate::color::Rgba<u8>) -> [T; N] {
+    pixel_from_color(crate::color::Rgba { a: 255, ..c })
+}
+
+/// the alpha of the color is multiplied to the coverage, and the alpha channel (if any)
+/// is composited as `alpha + dst_alpha * (1 - alpha)`
+impl<T, const N: usize> crate::canvas::Draw for Canvas<T, N>
+where
+    T: Channel,
+{
+    fn clear(&mut self, color: crate::color::Rgba<u8>) {
+        self.fill(pixel_from_color(color));
+    }
+
+    fn line(
+        &mut self,
+        p0: &[f32; 2],
+        p1: &[f32; 2],
+        transform_xy2pix: &[f32; 9],
+        style: &crate::canvas::Style,
+    ) {
+        let width = self.width;
+        crate::canvas::stroke_polyline(
+            self.pixels_mut(),
+            width,
+            &[p0[0], p0[1], p1[0], p1[1]],
+            false,
+            transform_xy2pix,
+            style,
+            &pixel_from_opaque_color::<T, N>,
+            &crate::blend::BlendMode::AlphaOver,
+        );
+    }
+
+    fn polygon(
+        &mut self,
+        vtx2xy: &[f32],
+        transform_xy2pix: &[f32; 9],
+        style: &crate::canvas::Style,
+    ) {
+        let width = self.width;
+        crate::canvas::draw_polygon(
+            self.pixels_mut(),
+            width,
+            vtx2xy,
+            transform_xy2pix,
+            style,
+            &pixel_from_opaque_color::<T, N>,
+            &crate::blend::BlendMode::AlphaOver,
+        );
+    }
+
+    fn circle(
+        &mut self,
+        center: &[f32; 2],
+        radius_pix: f32,
+        transform_xy2pix: &[f32; 9],
+        style: &crate::canvas::Style,
+    ) {
+        let vtx2xy = crate::canvas::circle_vtx2xy_pix(center, radius_pix, transform_xy2pix);
+        self.polygon(&vtx2xy, &[1., 0., 0., 0., 1., 0., 0., 0., 1.], style);
+    }
+}
 
-    pub fn write<P: AsRef<std::path::Path>>(&mut self, path_: P) -> anyhow::Result<()> {
-        let buffer: image::RgbImage =
-            image::ImageBuffer::from_raw(self.width as u32, self.height as u32, self.data.clone())
-                .unwrap();
-        Ok(buffer.save(path_)?)
+#[test]
+fn test_canvas_generic() -> anyhow::Result<()> {
+    let mut img = Canvas::<u16, 1>::filled((16, 8), [0]);
+    img.view_mut([4, 2, 12, 6]).fill([65535]);
+    assert_eq!(img.pixel(4, 2), &[65535]);
+    assert_eq!(img.pixel(3, 2), &[0]);
+    assert_eq!(img.row(5).iter().filter(|p| p[0] == 65535).count(), 8);
+    assert!(img.get_pixel(16, 0).is_none());
+    img.write("target/canvas_bitmap_gray16.png")?;
+    let img_read = image::open("target/canvas_bitmap_gray16.png")?.to_luma16();
+    assert_eq!(img_read.as_raw(), &img.data);
+    //
+    let view = img.view([10, 4, 100, 100]);
+    assert_eq!((view.width, view.height), (6, 4));
+    assert_eq!(view.pixel(1, 1), &[65535]);
+    assert_eq!(view.pixel(2, 1), &[0]);
+    let sub = view.to_canvas();
+    assert_eq!(sub.size(), (6, 4));
+    assert_eq!(sub.pixel(0, 0), &[65535]);
+    //
+    let rgba: Canvas<u8, 4> = img.to_channels::<4>().convert::<u8>();
+    assert_eq!(rgba.pixel(5, 3), &[255, 255, 255, 255]);
+    let mut rgb = Canvas::<f32, 3>::filled((16, 8), [1.0, 0.5, 0.0]);
+    rgb.view_mut([0, 0, 2, 2])
+        .copy_from(&rgba.to_channels::<3>().convert::<f32>());
+    assert_eq!(rgb.pixel(1, 1), &[0., 0., 0.]);
+    assert_eq!(rgb.pixel(2, 1), &[1.0, 0.5, 0.0]);
+    rgb.write("target/canvas_bitmap_rgb32f.hdr")?;
+    let gray = rgb.to_channels::<1>();
+    assert!((gray.pixel(5, 5)[0] - (0.2126 + 0.7152 * 0.5)).abs() < 1.0e-6);
+    assert!(Canvas::<u8, 3>::from_vec((2, 2), vec![0; 11]).is_err());
+    //
+    use crate::canvas::Draw;
+    let transform_xy2pix = [1., 0., 0., 0., 1., 0., 0., 0., 1.];
+    let mut rgba = Canvas::<u8, 4>::filled((16, 16), [0; 4]);
+    let style = crate::canvas::Style {
+        fill: Some(0xff0000.into()),
+        ..Default::default()
+    };
+    rgba.circle(&[8., 8.], 6., &transform_xy2pix, &style);
+    assert_eq!(rgba.pixel(8, 8), &[255, 0, 0, 255]);
+    assert_eq!(rgba.pixel(0, 0), &[0, 0, 0, 0]);
+    rgba.write("target/canvas_bitmap_rgba.png")?;
+    // the same pixels as the APNG canvas for the semi-transparent colors
+    let style = crate::canvas::Style {
+        stroke: Some(crate::color::Rgba::new(0, 0, 255, 200)),
+        stroke_width: Some(2.),
+        fill: Some(crate::color::Rgba::new(255, 0, 0, 128)),
+        opacity: Some(0.8),
+        ..Default::default()
+    };
+    let mut apng = crate::canvas_apng::Canvas::new("target/canvas_bitmap_apng.png", (16, 16));
+    for canvas in [&mut rgba as &mut dyn Draw, &mut apng] {
+        canvas.clear(crate::color::Rgba::new(0, 255, 0, 64));
+        canvas.circle(&[8., 8.], 5.5, &transform_xy2pix, &style);
+        canvas.line(&[1., 14.], &[14., 2.], &transform_xy2pix, &style);
     }
+    assert_eq!(rgba.data, apng.data);
+    Ok(())
 }
@@ -1,28 +1,47 @@
+pub use gif::{DisposalMethod, Repeat};
+
+/// how the full-color frames are converted to the palette indices
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Quantization {
+    /// nearest colors in the global palette shared by all the frames.
+    /// `Canvas::new_from_samples` computes it by the median cut of the sample frames
+    GlobalPalette,
+    /// local palette of at most `num_colors` (1 to 256) colors computed for each frame by the median cut.
+    /// If the frame has transparent pixels, one more entry is used for them (up to 255 colors then)
+    PerFrame { num_colors: usize },
+}
+
 pub struct Canvas {
     pub width: usize,
     pub height: usize,
     pub data: Vec<u8>,
-    gif_enc: Option<gif::Encoder<std::fs::File>>,
+    /// delay after the frame in the units of 10 milliseconds
+    pub delay: u16,
+    /// how the frame is treated before drawing the next frame
+    pub dispose: DisposalMethod,
+    /// palette index treated as transparent
+    pub transparent: Option<u8>,
+    palette: Vec<crate::color::Rgba<u8>>,
+    local_palette: Option<Vec<crate::color::Rgba<u8>>>,
+    gif_enc: Option<gif::Encoder<std::io::BufWriter<std::fs::File>>>,
 }
 
 impl Canvas {
-    pub fn new<Path>(path: Path, size: (usize, usize), palette: &[i32]) -> anyhow::Result<Self>
+    /// * `palette` - colors such as `0xRRGGBB` or `Rgba<u8>`. The alpha is ignored
+    pub fn new<Path, COLOR>(
+        path: Path,
+        size: (usize, usize),
+        palette: &[COLOR],
+    ) -> anyhow::Result<Self>
     where
         Path: AsRef<std::path::Path>,
+        COLOR: Into<crate::color::Rgba<u8>> + Copy,
     {
+        let palette: Vec<crate::color::Rgba<u8>> = palette.iter().map(|&c| c.into()).collect();
         let res_encoder = {
-            let global_palette = {
-                let mut res: Vec<u8> = vec![];
-                for &color in palette {
-                    let (r, g, b) = crate::color::u8rgb_from_i32(color);
-                    res.push(r);
-                    res.push(g);
-                    res.push(b);
-                }
-                res
-            };
+            let global_palette: Vec<u8> = palette.iter().flat_map(|c| c.rgb()).collect();
             gif::Encoder::new(
-                std::fs::File::create(path)?,
+                std::io::BufWriter::new(std::fs::File::create(path)?),
                 size.0 as u16,
                 size.1 as u16,
                 &global_palette,
@@ -35,6 +54,11 @@ impl Canvas {
                     width: size.0,
                     height: size.1,
                     data: vec![0; size.0 * size.1],
+                    delay: 0,
+                    dispose: DisposalMethod::Any,
+                    transparent: None,
+                    palette,
+                    local_palette: None,
                     gif_enc: Some(t),
                 };
                 Ok(s)
@@ -42,6 +66,77 @@ impl Canvas {
         }
     }
 
+    /// canvas whose global palette is computed by the median cut of the sample frames
+    /// (e.g., all or some of the frames to be written) for `Quantization::GlobalPalette`.
+    /// The pixels with alpha less than 128 are not sampled. If there are such pixels,
+    /// the index `0` is reserved for them and set to `transparent`.
+    /// * `samples` - RGBA images `[r0, g0, b0, a0, r1, ...]` of the canvas size
+    /// * `num_colors` - up to 256 including the transparent index
+    pub fn new_from_samples<Path>(
+        path: Path,
+        size: (usize, usize),
+        samples: &[&[u8]],
+        num_colors: usize,
+    ) -> anyhow::Result<Self>
+    where
+        Path: AsRef<std::path::Path>,
+    {
+        let mut opaque = vec![];
+        let mut has_transparent = false;
+        for rgba in samples {
+            anyhow::ensure!(
+                rgba.len() == size.0 * size.1 * 4,
+                "the size of the RGBA image does not match the canvas"
+            );
+            for c in rgba.chunks_exact(4) {
+                if c[3] < 128 {
+                    has_transparent = true;
+                } else {
+                    opaque.push([c[0], c[1], c[2]]);
+                }
+            }
+        }
+        let num_reserved = usize::from(has_transparent);
+        let mut palette = vec![crate::color::Rgba::TRANSPARENT; num_reserved];
+        palette.extend(
+            crate::quantize::median_cut(&opaque, num_colors.clamp(2, 256) - num_reserved)
+                .iter()
+                .map(|c| crate::color::Rgba::new(c[0], c[1], c[2], 255)),
+        );
+        anyhow::ensure!(!palette.is_empty(), "no sample pixel is given");
+        let mut canvas = Self::new(path, size, &palette)?;
+        canvas.transparent = has_transparent.then_some(0);
+        Ok(canvas)
+    }
+
+    /// number of the loops of the animation. This needs to be called before writing the first frame
+    pub fn set_repeat(&mut self, repeat: Repeat) -> anyhow::Result<()> {
+        match &mut self.gif_enc {
+            None => anyhow::bail!("the GIF is already finished"),
+            Some(enc) => enc.set_repeat(repeat)?,
+        }
+        Ok(())
+    }
+
+    /// use the local color table for the following frames instead of the global palette.
+    /// `None` goes back to the global palette
+    pub fn set_local_palette<COLOR>(&mut self, palette: Option<&[COLOR]>)
+    where
+        COLOR: Into<crate::color::Rgba<u8>> + Copy,
+    {
+        self.local_palette = palette.map(|p| p.iter().map(|&c| c.into()).collect());
+    }
+
+    /// the palette used for the current frame (the local one if set)
+    pub fn palette(&self) -> &[crate::color::Rgba<u8>] {
+        self.local_palette.as_deref().unwrap_or(&self.palette)
+    }
+
+    /// index of the palette color nearest to `color`
+    pub fn palette_index(&self, color: crate::color::Rgba<u8>) -> u8 {
+        color.nearest_index(self.palette()).unwrap_or(0) as u8
+    }
+
     pub fn clear(&mut self, color: u8) {
         for ih in 0..self.height {
             for iw in 0..self.width {
@@ -50,19 +145,575 @@ impl Canvas {
         }
     }
 
-    pub fn write(&mut self) {
-        // For reading and opening files
+    /// add the current `data` as a frame with the `delay`, `dispose`, `transparent` and local palette
+    pub fn write(&mut self) -> anyhow::Result<()> {
         let mut frame = gif::Frame {
             width: self.width as u16,
             height: self.height as u16,
+            delay: self.delay,
+            dispose: self.dispose,
+            transparent: self.transparent,
+            palette: self
+                .local_palette
+                .as_ref()
+                .map(|p| p.iter().flat_map(|c| c.rgb()).collect()),
             ..Default::default()
         };
         frame.buffer = std::borrow::Cow::Borrowed(&self.data);
         match &mut self.gif_enc {
-            None => {}
-            Some(enc) => {
-                let _ = &enc.write_frame(&frame);
+            None => anyhow::bail!("the GIF is already finished"),
+            Some(enc) => enc.write_frame(&frame)?,
+        }
+        Ok(())
+    }
+
+    /// add the RGB image (e.g., `canvas_bitmap::Canvas::data`) as a frame
+    /// * `rgb` - `[r0, g0, b0, r1, ...]` of the size `width * height * 3`
+    /// * `dither` - use the Floyd–Steinberg dithering
+    pub fn write_rgb(
+        &mut self,
+        rgb: &[u8],
+        quantization: Quantization,
+        dither: bool,
+    ) -> anyhow::Result<()> {
+        use slice_of_array::SliceNestExt;
+        anyhow::ensure!(
+            rgb.len() == self.width * self.height * 3,
+            "the size of the RGB image does not match the canvas"
+        );
+        self.write_quantized(rgb.nest(), None, quantization, dither)
+    }
+
+    /// add the RGBA image as a frame. The pixels with alpha less than 128 are transparent.
+    /// With `Quantization::GlobalPalette`, they use the `transparent` index (opaque if `None`).
+    /// With `Quantization::PerFrame`, the index `0` of the local palette is used for them.
+    /// The opaque pixels are never mapped to the transparent index
+    pub fn write_rgba(
+        &mut self,
+        rgba: &[u8],
+        quantization: Quantization,
+        dither: bool,
+    ) -> anyhow::Result<()> {
+        anyhow::ensure!(
+            rgba.len() == self.width * self.height * 4,
+            "the size of the RGBA image does not match the canvas"
+        );
+        let rgb: Vec<[u8; 3]> = rgba.chunks_exact(4).map(|c| [c[0], c[1], c[2]]).collect();
+        let is_transparent: Vec<bool> = rgba.chunks_exact(4).map(|c| c[3] < 128).collect();
+        self.write_quantized(&rgb, Some(&is_transparent), quantization, dither)
+    }
+
+    fn write_quantized(
+        &mut self,
+        rgb: &[[u8; 3]],
+        is_transparent: Option<&[bool]>,
+        quantization: Quantization,
+        dither: bool,
+    ) -> anyhow::Result<()> {
+        if let Quantization::PerFrame { num_colors } = quantization {
+            anyhow::ensure!(num_colors >= 1, "the palette needs at least 1 color");
+        }
+        let is_transparent = is_transparent.filter(|v| v.iter().any(|&b| b));
+        let (palette, transparent) = match quantization {
+            Quantization::GlobalPalette => {
+                let palette: Vec<[u8; 3]> = self.palette.iter().map(|c| c.rgb()).collect();
+                (palette, self.transparent)
             }
+            Quantization::PerFrame { num_colors } => match is_transparent {
+                None => (crate::quantize::median_cut(rgb, num_colors.min(256)), None),
+                Some(is_transparent) => {
+                    let opaque: Vec<[u8; 3]> = rgb
+                        .iter()
+                        .zip(is_transparent)
+                        .filter(|(_, &b)| !b)
+                        .map(|(&c, _)| c)
+                        .collect();
+                    let mut palette = vec![[0u8; 3]];
+                    palette.extend(crate::quantize::median_cut(&opaque, num_colors.min(255)));
+                    (palette, Some(0))
+                }
+            },
+        };
+        anyhow::ensure!(palette.len() <= 256, "the palette has more than 256 colors");
+        let is_transparent = is_transparent.filter(|_| transparent.is_some());
+        if is_transparent.is_some_and(|v| v.iter().all(|&b| b)) {
+            // no opaque pixel to quantize (e.g., the blank frame)
+            self.data = vec![0; rgb.len()];
+        } else {
+            // the opaque colors are not mapped to the transparent index
+            let candidates: Vec<usize> = (0..palette.len())
+                .filter(|&i| transparent.map(usize::from) != Some(i))
+                .collect();
+            let palette_opaque: Vec<[u8; 3]> = candidates.iter().map(|&i| palette[i]).collect();
+            let indices = crate::quantize::palette_indices(
+                rgb,
+                self.width,
+                &palette_opaque,
+                dither,
+                is_transparent,
+            )?;
+            self.data = indices
+                .into_iter()
+                .map(|i| u8::try_from(candidates[i as usize]))
+                .collect::<Result<_, _>>()?;
+        }
+        if let (Some(is_transparent), Some(i_transparent)) = (is_transparent, transparent) {
+            self.data
+                .iter_mut()
+                .zip(is_transparent)
+                .filter(|(_, &b)| b)
+                .for_each(|(i, _)| *i = i_transparent);
+        }
+        // the settings of the canvas are restored after writing this frame
+        let transparent = std::mem::replace(&mut self.transparent, transparent);
+        let local_palette = self.local_palette.take();
+        if matches!(quantization, Quantization::PerFrame { .. }) {
+            self.set_local_palette(Some(&palette));
+        }
+        let res = self.write();
+        self.transparent = transparent;
+        self.local_palette = local_palette;
+        res
+    }
+
+    /// write the trailer and flush the file. No frame can be added after this.
+    /// This is also done when the canvas is dropped, but the errors are ignored there
+    pub fn finish(&mut self) -> anyhow::Result<()> {
+        if let Some(enc) = self.gif_enc.take() {
+            use std::io::Write;
+            enc.into_inner()?.flush()?;
+        }
+        Ok(())
+    }
+}
+
+/// the pixel is painted if the coverage multiplied by the opacity is at least half
+/// because the palette cannot blend colors. The colors are replaced by the nearest ones in the palette.
+impl crate::canvas::Draw for Canvas {
+    fn clear(&mut self, color: crate::color::Rgba<u8>) {
+        let i_color = self.palette_index(color);
+        Canvas::clear(self, i_color);
+    }
+
+    fn line(
+        &mut self,
+        p0: &[f32; 2],
+        p1: &[f32; 2],
+        transform_xy2pix: &[f32; 9],
+        style: &crate::canvas::Style,
+    ) {
+        let palette = self.local_palette.as_deref().unwrap_or(&self.palette);
+        crate::canvas::stroke_polyline(
+            &mut self.data,
+            self.width,
+            &[p0[0], p0[1], p1[0], p1[1]],
+            false,
+            transform_xy2pix,
+            style,
+            &|c: crate::color::Rgba<u8>| c.nearest_index(palette).unwrap_or(0) as u8,
+            &blend_binary,
+        );
+    }
+
+    fn polygon(
+        &mut self,
+        vtx2xy: &[f32],
+        transform_xy2pix: &[f32; 9],
+        style: &crate::canvas::Style,
+    ) {
+        let palette = self.local_palette.as_deref().unwrap_or(&self.palette);
+        crate::canvas::draw_polygon(
+            &mut self.data,
+            self.width,
+            vtx2xy,
+            transform_xy2pix,
+            style,
+            &|c: crate::color::Rgba<u8>| c.nearest_index(palette).unwrap_or(0) as u8,
+            &blend_binary,
+        );
+    }
+
+    fn circle(
+        &mut self,
+        center: &[f32; 2],
+        radius_pix: f32,
+        transform_xy2pix: &[f32; 9],
+        style: &crate::canvas::Style,
+    ) {
+        let vtx2xy = crate::canvas::circle_vtx2xy_pix(center, radius_pix, transform_xy2pix);
+        self.polygon(&vtx2xy, &[1., 0., 0., 0., 1., 0., 0., 0., 1.], style);
+    }
+}
+
+fn blend_binary(dst: &mut u8, src: u8, coverage: f32) {
+    if coverage >= 0.5 {
+        *dst = src;
+    }
+}
+
+// ---------------------------
+// reading GIF
+
+/// frame of the decoded GIF composed on the whole logical screen
+#[derive(Debug, Clone)]
+pub struct AnimationFrame {
+    /// palette indices of the logical screen. They are meaningful only if
+    /// all the frames share the same palette. The cleared pixels have the transparent or background index
+    pub indices: Vec<u8>,
+    /// RGBA of the logical screen `[r0, g0, b0, a0, r1, ...]`. The alpha is `0` for the transparent pixels
+    pub rgba: Vec<u8>,
+    /// delay after the frame in the units of 10 milliseconds
+    pub delay: u16,
+    pub dispose: DisposalMethod,
+    pub transparent: Option<u8>,
+    /// palette used by this frame (the local palette if exists, otherwise the global palette)
+    pub palette: Vec<crate::color::Rgba<u8>>,
+}
+
+impl AnimationFrame {
+    /// RGB image where the transparent pixels are filled with `background`
+    pub fn rgb(&self, background: crate::color::Rgba<u8>) -> Vec<u8> {
+        let bg = background.rgb();
+        self.rgba
+            .chunks_exact(4)
+            .flat_map(|c| if c[3] == 0 { bg } else { [c[0], c[1], c[2]] })
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Animation {
+    pub width: usize,
+    pub height: usize,
+    pub global_palette: Vec<crate::color::Rgba<u8>>,
+    pub repeat: Repeat,
+    pub frames: Vec<AnimationFrame>,
+}
+
+/// decode the GIF file. See `decode` for the details
+pub fn read<P: AsRef<std::path::Path>>(path: P) -> anyhow::Result<Animation> {
+    let file = std::fs::File::open(path)?;
+    decode(std::io::BufReader::new(file))
+}
+
+/// decode the GIF into the frames composed on the logical screen
+/// following the disposal method of the previous frame.
+/// The disposal to the background clears the pixels to transparent as the web browsers do.
+pub fn decode<R: std::io::Read>(reader: R) -> anyhow::Result<Animation> {
+    let to_palette = |p: &[u8]| -> Vec<crate::color::Rgba<u8>> {
+        p.chunks_exact(3)
+            .map(|c| crate::color::Rgba::new(c[0], c[1], c[2], 255))
+            .collect()
+    };
+    let mut options = gif::DecodeOptions::new();
+    options.set_color_output(gif::ColorOutput::Indexed);
+    let mut decoder = options.read_info(reader)?;
+    let (width, height) = (decoder.width() as usize, decoder.height() as usize);
+    let global_palette = to_palette(decoder.global_palette().unwrap_or(&[]));
+    let bg_index = decoder.bg_color().unwrap_or(0) as u8;
+    let mut indices = vec![bg_index; width * height];
+    let mut rgba = vec![0u8; width * height * 4];
+    // disposal of the previous frame: the method, the rectangle, the index to clear and the saved screen
+    type Screen = (Vec<u8>, Vec<u8>);
+    let mut disposal: Option<(DisposalMethod, [usize; 4], u8, Option<Screen>)> = None;
+    let mut frames = vec![];
+    while let Some(frame) = decoder.read_next_frame()? {
+        match disposal.take() {
+            Some((DisposalMethod::Background, [x0, y0, w, h], i_clear, _)) => {
+                for iy in y0..(y0 + h).min(height) {
+                    for ix in x0..(x0 + w).min(width) {
+                        let i_pix = iy * width + ix;
+                        indices[i_pix] = i_clear;
+                        rgba[i_pix * 4..i_pix * 4 + 4].copy_from_slice(&[0; 4]);
+                    }
+                }
+            }
+            Some((DisposalMethod::Previous, _, _, Some(saved))) => (indices, rgba) = saved,
+            _ => {}
+        }
+        let palette = match &frame.palette {
+            Some(p) => to_palette(p),
+            None => global_palette.clone(),
+        };
+        let rect = [
+            frame.left as usize,
+            frame.top as usize,
+            frame.width as usize,
+            frame.height as usize,
+        ];
+        let saved =
+            (frame.dispose == DisposalMethod::Previous).then(|| (indices.clone(), rgba.clone()));
+        for iy in 0..rect[3] {
+            for ix in 0..rect[2] {
+                let (px, py) = (rect[0] + ix, rect[1] + iy);
+                let i_color = frame.buffer[iy * rect[2] + ix];
+                if px >= width || py >= height || frame.transparent == Some(i_color) {
+                    continue;
+                }
+                let i_pix = py * width + px;
+                indices[i_pix] = i_color;
+                let c = palette
+                    .get(i_color as usize)
+                    .copied()
+                    .unwrap_or(crate::color::Rgba::BLACK);
+                rgba[i_pix * 4..i_pix * 4 + 4].copy_from_slice(&[c.r, c.g, c.b, 255]);
+            }
+        }
+        frames.push(AnimationFrame {
+            indices: indices.clone(),
+            rgba: rgba.clone(),
+            delay: frame.delay,
+            dispose: frame.dispose,
+            transparent: frame.transparent,
+            palette,
+        });
+        let i_clear = frame.transparent.unwrap_or(bg_index);
+        disposal = Some((frame.dispose, rect, i_clear, saved));
+    }
+    Ok(Animation {
+        width,
+        height,
+        global_palette,
+        repeat: decoder.repeat(),
+        frames,
+    })
+}
+
+#[test]
+fn test_animation() -> anyhow::Result<()> {
+    use crate::canvas::Draw;
+    let path = "target/canvas_gif_animation.gif";
+    let img_size = (64, 48);
+    let palette = [0xffffff, 0x000000, 0xff0000];
+    let mut canvas = Canvas::new(path, img_size, &palette)?;
+    canvas.set_repeat(Repeat::Infinite)?;
+    canvas.delay = 20;
+    canvas.dispose = DisposalMethod::Background;
+    canvas.transparent = Some(0);
+    let transform_xy2pix = [1., 0., 0., 0., 1., 0., 0., 0., 1.];
+    for i_frame in 0..4 {
+        if i_frame == 2 {
+            canvas.set_local_palette(Some(&[0xffffff, 0x0000ff]));
+        }
+        canvas.clear(0);
+        let style = crate::canvas::Style {
+            fill: Some(0xff0000.into()),
+            ..Default::default()
+        };
+        let center = [10. + 14. * i_frame as f32, 24.];
+        Draw::circle(&mut canvas, &center, 8., &transform_xy2pix, &style);
+        canvas.write()?;
+    }
+    canvas.finish()?;
+    assert!(canvas.write().is_err());
+    //
+    let mut options = gif::DecodeOptions::new();
+    options.set_color_output(gif::ColorOutput::Indexed);
+    let mut decoder = options.read_info(std::fs::File::open(path)?)?;
+    let mut num_frame = 0;
+    while let Some(frame) = decoder.read_next_frame()? {
+        assert_eq!(frame.delay, 20);
+        assert_eq!(frame.dispose, DisposalMethod::Background);
+        assert_eq!(frame.transparent, Some(0));
+        // local color table of the two colors
+        assert_eq!(
+            frame.palette.as_ref().map(|p| p.len()),
+            (num_frame >= 2).then_some(6)
+        );
+        num_frame += 1;
+    }
+    assert_eq!(num_frame, 4);
+    assert_eq!(decoder.repeat(), Repeat::Infinite);
+    Ok(())
+}
+
+#[test]
+fn test_write_rgb() -> anyhow::Result<()> {
+    use crate::canvas::Draw;
+    let img_size = (64, 64);
+    let path = "target/canvas_gif_rgb.gif";
+    let mut canvas = Canvas::new(path, img_size, &[0x000000, 0xffffff, 0xff0000, 0x0000ff])?;
+    canvas.delay = 10;
+    let mut bitmap = crate::canvas_bitmap::Canvas::new(img_size);
+    let transform_xy2pix = [64., 0., 0., 0., -64., 0., 0., 64., 1.];
+    let mut frames = vec![];
+    for i_frame in 0..3 {
+        // smooth gradient that needs the dithering with the small palette
+        for iy in 0..img_size.1 {
+            for ix in 0..img_size.0 {
+                let i_pix = iy * img_size.0 + ix;
+                bitmap.data[i_pix * 3..i_pix * 3 + 3].copy_from_slice(&[
+                    (ix * 4) as u8,
+                    (iy * 4) as u8,
+                    128,
+                ]);
+            }
+        }
+        let style = crate::canvas::Style {
+            fill: Some(0xff0000.into()),
+            ..Default::default()
+        };
+        let center = [0.25 + 0.25 * i_frame as f32, 0.5];
+        Draw::circle(&mut bitmap, &center, 10., &transform_xy2pix, &style);
+        frames.push(bitmap.data.clone());
+    }
+    canvas.write_rgb(&frames[0], Quantization::GlobalPalette, true)?;
+    canvas.write_rgb(&frames[1], Quantization::PerFrame { num_colors: 16 }, false)?;
+    let rgba: Vec<u8> = frames[2]
+        .chunks_exact(3)
+        .enumerate()
+        .flat_map(|(i, c)| [c[0], c[1], c[2], if i % 64 < 32 { 255 } else { 0 }])
+        .collect();
+    canvas.write_rgba(&rgba, Quantization::PerFrame { num_colors: 16 }, true)?;
+    assert!(canvas.local_palette.is_none());
+    assert_eq!(canvas.transparent, None);
+    assert!(canvas
+        .write_rgb(&frames[0][3..], Quantization::GlobalPalette, false)
+        .is_err());
+    canvas.finish()?;
+    //
+    let mut options = gif::DecodeOptions::new();
+    options.set_color_output(gif::ColorOutput::Indexed);
+    let mut decoder = options.read_info(std::fs::File::open(path)?)?;
+    let frame = decoder.read_next_frame()?.unwrap();
+    assert!(frame.palette.is_none());
+    assert!(frame.buffer.iter().all(|&i| i < 4));
+    let frame = decoder.read_next_frame()?.unwrap();
+    assert_eq!(frame.palette.as_ref().map(|p| p.len()), Some(16 * 3));
+    let frame = decoder.read_next_frame()?.unwrap();
+    assert_eq!(frame.transparent, Some(0));
+    assert_eq!(frame.buffer[63], 0);
+    assert_ne!(frame.buffer[0], 0);
+    Ok(())
+}
+
+#[test]
+fn test_global_palette() -> anyhow::Result<()> {
+    let img_size = (8, 8);
+    let path = "target/canvas_gif_global_palette.gif";
+    // the transparent index has the same color as the opaque red pixels
+    let mut canvas = Canvas::new(path, img_size, &[0xff0000, 0xffffff, 0xff0000, 0x0000ff])?;
+    canvas.transparent = Some(0);
+    let red_and_transparent: Vec<u8> = (0..img_size.0 * img_size.1)
+        .flat_map(|i| {
+            if i < 32 {
+                [255, 0, 0, 255]
+            } else {
+                [0, 0, 0, 0]
+            }
+        })
+        .collect();
+    canvas.write_rgba(&red_and_transparent, Quantization::GlobalPalette, true)?;
+    assert!(canvas.data[..32].iter().all(|&i| i == 2));
+    assert!(canvas.data[32..].iter().all(|&i| i == 0));
+    canvas.write_rgb(&[255, 0, 0].repeat(64), Quantization::GlobalPalette, false)?;
+    assert!(canvas.data.iter().all(|&i| i == 2));
+    canvas.finish()?;
+    let animation = read(path)?;
+    assert_eq!(&animation.frames[0].rgba[..4], &[255, 0, 0, 255]);
+    assert_eq!(&animation.frames[0].rgba[32 * 4..33 * 4], &[0, 0, 0, 0]);
+    //
+    // one palette computed from the frames with the different colors
+    let frames: Vec<Vec<u8>> = [[0u8, 255, 0, 255], [0, 0, 255, 255], [0, 0, 0, 0]]
+        .iter()
+        .map(|c| {
+            (0..img_size.0 * img_size.1)
+                .flat_map(|i| if i % 2 == 0 { *c } else { [255; 4] })
+                .collect()
+        })
+        .collect();
+    let samples: Vec<&[u8]> = frames.iter().map(|f| f.as_slice()).collect();
+    let mut canvas = Canvas::new_from_samples(path, img_size, &samples, 16)?;
+    assert_eq!(canvas.transparent, Some(0));
+    assert_eq!(canvas.palette().len(), 4);
+    canvas.dispose = DisposalMethod::Background;
+    for frame in frames.iter() {
+        canvas.write_rgba(frame, Quantization::GlobalPalette, true)?;
+    }
+    canvas.finish()?;
+    let animation = read(path)?;
+    for (frame, decoded) in frames.iter().zip(animation.frames.iter()) {
+        assert_eq!(frame, &decoded.rgba);
+    }
+    // the blank frame has no opaque pixel to quantize
+    let path = "target/canvas_gif_blank.gif";
+    let blank = vec![0u8; img_size.0 * img_size.1 * 4];
+    let mut canvas = Canvas::new(path, img_size, &[0x000000, 0xffffff])?;
+    assert!(canvas
+        .write_rgba(&blank, Quantization::PerFrame { num_colors: 0 }, false)
+        .is_err());
+    canvas.write_rgba(&blank, Quantization::PerFrame { num_colors: 16 }, false)?;
+    canvas.transparent = Some(1);
+    canvas.write_rgba(&blank, Quantization::GlobalPalette, false)?;
+    assert!(canvas.data.iter().all(|&i| i == 1));
+    canvas.finish()?;
+    let animation = read(path)?;
+    assert_eq!(animation.frames.len(), 2);
+    assert!(animation.frames.iter().all(|f| f.rgba == blank));
+    Ok(())
+}
+
+#[test]
+fn test_read() -> anyhow::Result<()> {
+    // frames of the sub-rectangles with the different disposal methods
+    let path = "target/canvas_gif_disposal.gif";
+    {
+        let global_palette = [255, 255, 255, 0, 0, 0, 255, 0, 0, 0, 0, 255];
+        let mut enc = gif::Encoder::new(std::fs::File::create(path)?, 4, 4, &global_palette)?;
+        let frames = [
+            ((0, 0, 4, 4), vec![0; 16], DisposalMethod::Keep, None),
+            ((1, 1, 2, 2), vec![2; 4], DisposalMethod::Background, None),
+            ((0, 0, 1, 1), vec![3], DisposalMethod::Previous, None),
+            ((2, 3, 2, 1), vec![0, 1], DisposalMethod::Keep, Some(0)),
+        ];
+        for ((left, top, width, height), buffer, dispose, transparent) in frames {
+            let frame = gif::Frame {
+                left,
+                top,
+                width,
+                height,
+                buffer: std::borrow::Cow::Owned(buffer),
+                dispose,
+                transparent,
+                delay: 5,
+                ..Default::default()
+            };
+            enc.write_frame(&frame)?;
         }
     }
+    let animation = read(path)?;
+    assert_eq!((animation.width, animation.height), (4, 4));
+    assert_eq!(animation.frames.len(), 4);
+    let pix = |i_frame: usize, x: usize, y: usize| {
+        let i_pix = y * 4 + x;
+        <[u8; 4]>::try_from(&animation.frames[i_frame].rgba[i_pix * 4..i_pix * 4 + 4]).unwrap()
+    };
+    assert_eq!(pix(1, 1, 1), [255, 0, 0, 255]);
+    assert_eq!(pix(1, 0, 0), [255, 255, 255, 255]);
+    // the red square is cleared by the background disposal
+    assert_eq!(pix(2, 1, 1), [0, 0, 0, 0]);
+    assert_eq!(pix(2, 0, 0), [0, 0, 255, 255]);
+    // the blue pixel is removed by the previous disposal and the transparent pixel keeps white
+    assert_eq!(pix(3, 0, 0), [255, 255, 255, 255]);
+    assert_eq!(pix(3, 2, 3), [255, 255, 255, 255]);
+    assert_eq!(pix(3, 3, 3), [0, 0, 0, 255]);
+    assert_eq!(animation.frames[3].indices[15], 1);
+    assert_eq!(animation.frames[3].delay, 5);
+    assert_eq!(
+        &animation.frames[2].rgb(crate::color::Rgba::new(0, 255, 0, 255))[15..18],
+        &[0, 255, 0]
+    );
+    // round trip of the canvas
+    let path = "target/canvas_gif_roundtrip.gif";
+    let mut canvas = Canvas::new(path, (8, 8), &[0x000000, 0x00ff00])?;
+    canvas.data[9] = 1;
+    canvas.write()?;
+    canvas.finish()?;
+    let animation = read(path)?;
+    assert_eq!(animation.frames[0].indices, canvas.data);
+    assert_eq!(
+        animation.global_palette[1],
+        crate::color::Rgba::new(0, 255, 0, 255)
+    );
+    Ok(())
 }
@@ -3,6 +3,18 @@ pub struct Canvas {
     pub height: usize,
     pub file_path: String,
     pub tags: Vec<String>,
+    /// contents of `<defs>` such as the arrow markers
+    pub defs: Vec<String>,
+}
+
+pub use crate::canvas::{LineCap, LineJoin, Style};
+use crate::color::Rgba;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TextAnchor {
+    Start,
+    Middle,
+    End,
 }
 
 impl crate::canvas_svg::Canvas {
@@ -12,103 +24,526 @@ impl crate::canvas_svg::Canvas {
             height: size.1,
             file_path,
             tags: vec![],
+            defs: vec![],
         }
     }
 
-    pub fn write(&self) {
-        let mut file = std::fs::File::create(self.file_path.as_str()).expect("file not found.");
+    /// write the SVG into the file at `file_path`
+    pub fn write(&self) -> anyhow::Result<()> {
+        let file = std::fs::File::create(self.file_path.as_str())?;
+        let mut writer = std::io::BufWriter::new(file);
+        self.write_to(&mut writer)?;
         use std::io::Write;
-        writeln!(
-            file,
-            "<svg width=\"{}\" height=\"{}\" xmlns=\"http://www.w3.org/2000/svg\">",
-            self.width, self.height
-        )
-        .expect("cannot write.");
-        for s in &self.tags {
-            writeln!(file, "{}", s).expect("cannot write");
-        }
-        writeln!(file, "</svg>").expect("cannot write");
+        writer.flush()?;
+        Ok(())
+    }
+
+    /// write the SVG into any writer such as `Vec<u8>`, file or network stream
+    pub fn write_to<W: std::io::Write>(&self, writer: &mut W) -> anyhow::Result<()> {
+        write!(writer, "{}", self)?;
+        Ok(())
     }
 
+    /// closed polygon. `None` for `stroke="none"` or `fill="none"`
+    /// * `vtx2xy` - coordinates stored as `[x0, y0, x1, y1, ...]`
+    /// * `transform_xy2pix` - 3x3 homogeneous transformation matrix with **column major** order
     pub fn polyloop(
         &mut self,
         vtx2xy: &[f32],
         transform_xy2pix: &[f32; 9],
-        stroke_color: Option<i32>,
+        stroke_color: Option<Rgba<u8>>,
         stroke_width: Option<f32>,
-        fill: Option<i32>,
+        fill: Option<Rgba<u8>>,
+    ) {
+        let style = Style {
+            stroke: stroke_color,
+            stroke_width,
+            fill,
+            ..Default::default()
+        };
+        self.polygon(vtx2xy, transform_xy2pix, &style);
+    }
+
+    /// filled circle with the radius in the pixel coordinate. See `Draw::circle` for the stroke
+    /// * `transform_xy2pix` - 3x3 homogeneous transformation matrix with **column major** order
+    pub fn circle_rgba(
+        &mut self,
+        x: f32,
+        y: f32,
+        transform_xy2pix: &[f32; 9],
+        radius: f32,
+        color: Rgba<u8>,
+    ) {
+        let style = Style {
+            stroke: None,
+            fill: Some(color),
+            ..Default::default()
+        };
+        crate::canvas::Draw::circle(self, &[x, y], radius, transform_xy2pix, &style);
+    }
+
+    /// line segment from `(x1, y1)` to `(x2, y2)`. Same as `segment`
+    /// * `transform_xy2pix` - 3x3 homogeneous transformation matrix with **column major** order
+    pub fn line_xy(
+        &mut self,
+        x1: f32,
+        y1: f32,
+        x2: f32,
+        y2: f32,
+        transform_xy2pix: &[f32; 9],
+        style: &Style,
+    ) {
+        self.segment(&[x1, y1], &[x2, y2], transform_xy2pix, style);
+    }
+
+    /// the attributes of `style` starting with a space.
+    /// The arrow markers are added to `defs` if they are not defined yet.
+    fn style_attributes(&mut self, style: &Style) -> String {
+        self.paint_attributes(style, None)
+    }
+
+    /// same as `style_attributes` but the fill is `url(#fill_id)` if `fill_id` is given
+    fn paint_attributes(&mut self, style: &Style, fill_id: Option<&str>) -> String {
+        let mut s = String::new();
+        match style.stroke {
+            Some(stroke) => {
+                s += &format!(" stroke=\"{}\"", stroke.to_hex_rgb());
+                if stroke.a != 255 {
+                    s += &format!(" stroke-opacity=\"{}\"", stroke.opacity());
+                }
+            }
+            None => s += " stroke=\"none\"",
+        }
+        if let Some(stroke_width) = style.stroke_width {
+            s += &format!(" stroke-width=\"{}\"", stroke_width);
+        }
+        match (fill_id, style.fill) {
+            (Some(id), _) => s += &format!(" fill=\"url(#{})\"", id),
+            (None, Some(fill)) => {
+                s += &format!(" fill=\"{}\"", fill.to_hex_rgb());
+                if fill.a != 255 {
+                    s += &format!(" fill-opacity=\"{}\"", fill.opacity());
+                }
+            }
+            (None, None) => s += " fill=\"none\"",
+        }
+        if let Some(opacity) = style.opacity {
+            s += &format!(" opacity=\"{}\"", opacity);
+        }
+        if !style.dash.is_empty() {
+            let dash: Vec<String> = style.dash.iter().map(|v| v.to_string()).collect();
+            s += &format!(" stroke-dasharray=\"{}\"", dash.join(" "));
+        }
+        if let Some(line_cap) = style.line_cap {
+            let v = match line_cap {
+                LineCap::Butt => "butt",
+                LineCap::Round => "round",
+                LineCap::Square => "square",
+            };
+            s += &format!(" stroke-linecap=\"{}\"", v);
+        }
+        if let Some(line_join) = style.line_join {
+            let v = match line_join {
+                LineJoin::Miter => "miter",
+                LineJoin::Round => "round",
+                LineJoin::Bevel => "bevel",
+            };
+            s += &format!(" stroke-linejoin=\"{}\"", v);
+        }
+        if style.arrow_start || style.arrow_end {
+            // the marker is defined for each color because `context-stroke` is not widely supported
+            let color = style.stroke.unwrap_or(Rgba::BLACK).to_hex_rgb();
+            let id = format!("arrow-{}", &color[1..]);
+            if !self
+                .defs
+                .iter()
+                .any(|d| d.contains(&format!("id=\"{}\"", id)))
+            {
+                self.defs.push(format!(
+                    "<marker id=\"{}\" viewBox=\"0 0 10 10\" refX=\"10\" refY=\"5\" \
+                    markerWidth=\"6\" markerHeight=\"6\" orient=\"auto-start-reverse\">\
+                    <path d=\"M 0 0 L 10 5 L 0 10 z\" fill=\"{}\" /></marker>",
+                    id, color
+                ));
+            }
+            if style.arrow_start {
+                s += &format!(" marker-start=\"url(#{})\"", id);
+            }
+            if style.arrow_end {
+                s += &format!(" marker-end=\"url(#{})\"", id);
+            }
+        }
+        s
+    }
+
+    /// line segment from `p0` to `p1`
+    /// * `transform_xy2pix` - 3x3 homogeneous transformation matrix with **column major** order
+    pub fn segment(
+        &mut self,
+        p0: &[f32; 2],
+        p1: &[f32; 2],
+        transform_xy2pix: &[f32; 9],
+        style: &Style,
     ) {
+        let q0 = del_geo_core::mat3_col_major::transform_homogeneous(transform_xy2pix, p0).unwrap();
+        let q1 = del_geo_core::mat3_col_major::transform_homogeneous(transform_xy2pix, p1).unwrap();
         let s = format!(
-            "<polygon points=\"{}\" {} {} {} />",
+            "<line x1=\"{}\" y1=\"{}\" x2=\"{}\" y2=\"{}\"{} />",
+            q0[0],
+            q0[1],
+            q1[0],
+            q1[1],
+            self.style_attributes(style)
+        );
+        self.tags.push(s);
+    }
+
+    /// open polyline
+    /// * `vtx2xy` - coordinates stored as `[x0, y0, x1, y1, ...]`
+    /// * `transform_xy2pix` - 3x3 homogeneous transformation matrix with **column major** order
+    pub fn polyline(&mut self, vtx2xy: &[f32], transform_xy2pix: &[f32; 9], style: &Style) {
+        let s = format!(
+            "<polyline points=\"{}\"{} />",
             polyloop2_to_svg(vtx2xy, transform_xy2pix),
-            if stroke_color.is_some() {
-                format!("stroke=\"#{:06X}\"", stroke_color.unwrap())
-            } else {
-                "stroke=\"none\"".to_owned()
-            },
-            if stroke_width.is_some() {
-                format!("stroke-width=\"{}\"", stroke_width.unwrap())
-            } else {
-                "".to_owned()
-            },
-            if fill.is_some() {
-                format!("fill=\"#{:06X}\"", fill.unwrap())
-            } else {
-                "fill=\"none\"".to_owned()
+            self.style_attributes(style)
+        );
+        self.tags.push(s);
+    }
+
+    /// closed polygon. Same as `polyloop` but the appearance is given by `style`
+    /// * `vtx2xy` - coordinates stored as `[x0, y0, x1, y1, ...]`
+    /// * `transform_xy2pix` - 3x3 homogeneous transformation matrix with **column major** order
+    pub fn polygon(&mut self, vtx2xy: &[f32], transform_xy2pix: &[f32; 9], style: &Style) {
+        let s = format!(
+            "<polygon points=\"{}\"{} />",
+            polyloop2_to_svg(vtx2xy, transform_xy2pix),
+            self.style_attributes(style)
+        );
+        self.tags.push(s);
+    }
+
+    /// axis-aligned rectangle in the `xy` coordinate. It is written as a polygon so that
+    /// the rectangle is correctly drawn for any transformation.
+    /// * `aabb` - `[x_min, y_min, x_max, y_max]`
+    /// * `transform_xy2pix` - 3x3 homogeneous transformation matrix with **column major** order
+    pub fn rect(&mut self, aabb: &[f32; 4], transform_xy2pix: &[f32; 9], style: &Style) {
+        let vtx2xy = [
+            aabb[0], aabb[1], aabb[2], aabb[1], aabb[2], aabb[3], aabb[0], aabb[3],
+        ];
+        self.polygon(&vtx2xy, transform_xy2pix, style);
+    }
+
+    /// `rect` filled with the element in `<defs>` (e.g., gradient) of the id returned by `add_def`.
+    /// The fill of `style` is ignored
+    pub fn rect_with_paint(
+        &mut self,
+        aabb: &[f32; 4],
+        transform_xy2pix: &[f32; 9],
+        fill_id: &str,
+        style: &Style,
+    ) {
+        let vtx2xy = [
+            aabb[0], aabb[1], aabb[2], aabb[1], aabb[2], aabb[3], aabb[0], aabb[3],
+        ];
+        let s = format!(
+            "<polygon points=\"{}\"{} />",
+            polyloop2_to_svg(&vtx2xy, transform_xy2pix),
+            self.paint_attributes(style, Some(fill_id))
+        );
+        self.tags.push(s);
+    }
+
+    /// add the element such as `<linearGradient ...>...</linearGradient>` without the `id`
+    /// attribute to `<defs>`, and return the id not used by the other elements in `<defs>`
+    pub fn add_def(&mut self, element: &str) -> String {
+        let id = (self.defs.len()..)
+            .map(|i| format!("def-{}", i))
+            .find(|id| {
+                let attr = format!("id=\"{}\"", id);
+                !self.defs.iter().any(|d| d.contains(&attr))
+            })
+            .unwrap();
+        // the id is put right after the tag name
+        let i_name_end = element
+            .char_indices()
+            .skip(1)
+            .find(|&(_, c)| c.is_whitespace() || c == '>' || c == '/')
+            .map_or(element.len(), |(i, _)| i);
+        self.defs.push(format!(
+            "{} id=\"{}\"{}",
+            &element[..i_name_end],
+            id,
+            &element[i_name_end..]
+        ));
+        id
+    }
+
+    /// axis-aligned ellipse in the `xy` coordinate. It is written as a path of cubic Bézier
+    /// curves so that the ellipse is correctly drawn for any affine transformation.
+    /// * `transform_xy2pix` - 3x3 homogeneous transformation matrix with **column major** order
+    pub fn ellipse(
+        &mut self,
+        cx: f32,
+        cy: f32,
+        rx: f32,
+        ry: f32,
+        transform_xy2pix: &[f32; 9],
+        style: &Style,
+    ) {
+        use crate::svg::path::{Segment, SubPath};
+        let arc = |end: [f32; 2]| Segment::Arc {
+            radii: [rx, ry],
+            x_axis_rotation: 0.,
+            large_arc: false,
+            sweep: true,
+            end,
+        };
+        let subpath = SubPath {
+            start: [cx + rx, cy],
+            segments: vec![arc([cx - rx, cy]), arc([cx + rx, cy])],
+            is_closed: true,
+        };
+        self.path(&[subpath], transform_xy2pix, style);
+    }
+
+    /// cubic Bézier curve with the end points `p0`, `p3` and the control points `p1`, `p2`
+    /// * `transform_xy2pix` - 3x3 homogeneous transformation matrix with **column major** order
+    pub fn cubic_bezier(&mut self, p: &[[f32; 2]; 4], transform_xy2pix: &[f32; 9], style: &Style) {
+        let subpath = crate::svg::path::SubPath {
+            start: p[0],
+            segments: vec![crate::svg::path::Segment::Cubic(p[1], p[2], p[3])],
+            is_closed: false,
+        };
+        self.path(&[subpath], transform_xy2pix, style);
+    }
+
+    /// quadratic Bézier curve with the end points `p0`, `p2` and the control point `p1`
+    /// * `transform_xy2pix` - 3x3 homogeneous transformation matrix with **column major** order
+    pub fn quadratic_bezier(
+        &mut self,
+        p: &[[f32; 2]; 3],
+        transform_xy2pix: &[f32; 9],
+        style: &Style,
+    ) {
+        let subpath = crate::svg::path::SubPath {
+            start: p[0],
+            segments: vec![crate::svg::path::Segment::Quadratic(p[1], p[2])],
+            is_closed: false,
+        };
+        self.path(&[subpath], transform_xy2pix, style);
+    }
+
+    /// general path made of lines, Bézier curves and arcs (e.g., parsed by `svg::path::parse`).
+    /// The arcs are converted into cubic Bézier curves before the transformation.
+    /// * `transform_xy2pix` - 3x3 homogeneous transformation matrix with **column major** order
+    pub fn path(
+        &mut self,
+        subpaths: &[crate::svg::path::SubPath],
+        transform_xy2pix: &[f32; 9],
+        style: &Style,
+    ) {
+        use crate::svg::path::Segment;
+        use std::fmt::Write;
+        let pix = |p: &[f32; 2]| {
+            let q =
+                del_geo_core::mat3_col_major::transform_homogeneous(transform_xy2pix, p).unwrap();
+            format!("{} {}", q[0], q[1])
+        };
+        let mut d = String::new();
+        for subpath in subpaths {
+            write!(d, "M {} ", pix(&subpath.start)).unwrap();
+            let mut pos_cur = subpath.start;
+            for segment in subpath.segments.iter() {
+                match segment {
+                    Segment::Line(p1) => write!(d, "L {} ", pix(p1)).unwrap(),
+                    Segment::Quadratic(pc, p1) => {
+                        write!(d, "Q {} {} ", pix(pc), pix(p1)).unwrap();
+                    }
+                    Segment::Cubic(pc0, pc1, p1) => {
+                        write!(d, "C {} {} {} ", pix(pc0), pix(pc1), pix(p1)).unwrap();
+                    }
+                    Segment::Arc {
+                        radii,
+                        x_axis_rotation,
+                        large_arc,
+                        sweep,
+                        end,
+                    } => {
+                        let cubics = crate::svg::path::arc_to_cubics(
+                            &pos_cur,
+                            radii,
+                            *x_axis_rotation,
+                            *large_arc,
+                            *sweep,
+                            end,
+                        );
+                        for cubic in cubics {
+                            match cubic {
+                                Segment::Cubic(pc0, pc1, p1) => {
+                                    write!(d, "C {} {} {} ", pix(&pc0), pix(&pc1), pix(&p1))
+                                        .unwrap();
+                                }
+                                Segment::Line(p1) => write!(d, "L {} ", pix(&p1)).unwrap(),
+                                _ => unreachable!(),
+                            }
+                        }
+                    }
+                }
+                pos_cur = segment.end();
             }
+            if subpath.is_closed {
+                d += "Z ";
+            }
+        }
+        let s = format!(
+            "<path d=\"{}\"{} />",
+            d.trim_end(),
+            self.style_attributes(style)
         );
         self.tags.push(s);
     }
 
-    pub fn circle(
+    /// text label whose baseline starts at `(x, y)`
+    /// * `transform_xy2pix` - 3x3 homogeneous transformation matrix with **column major** order
+    /// * `font_size` - font size in the pixel coordinate
+    #[allow(clippy::too_many_arguments)]
+    pub fn text(
         &mut self,
         x: f32,
         y: f32,
         transform_xy2pix: &[f32; 9],
-        radius: f32,
-        color: &str,
+        text: &str,
+        font_size: f32,
+        color: Rgba<u8>,
+        anchor: TextAnchor,
     ) {
-        let p = [x, y, 1.];
-        let q = del_geo_core::mat3_col_major::mult_vec(transform_xy2pix, &p);
+        let q =
+            del_geo_core::mat3_col_major::transform_homogeneous(transform_xy2pix, &[x, y]).unwrap();
+        let anchor = match anchor {
+            TextAnchor::Start => "start",
+            TextAnchor::Middle => "middle",
+            TextAnchor::End => "end",
+        };
         let s = format!(
-            "<circle cx=\"{}\" cy=\"{}\" r=\"{}\" fill=\"{}\" />",
-            q[0] / q[2],
-            q[1] / q[2],
-            radius,
-            color
+            "<text x=\"{}\" y=\"{}\" font-size=\"{}\" fill=\"{}\" text-anchor=\"{}\">{}</text>",
+            q[0],
+            q[1],
+            font_size,
+            color.to_hex_rgb(),
+            anchor,
+            escape_xml(text)
         );
         self.tags.push(s);
     }
 
-    pub fn line(
+    /// start the group `<g>`. The elements until `end_group` are transformed by `transform_pix`
+    /// **in the pixel coordinate** and share the `opacity`.
+    /// * `transform_pix` - 3x3 affine transformation matrix with **column major** order
+    pub fn begin_group(&mut self, transform_pix: Option<&[f32; 9]>, opacity: Option<f32>) {
+        let mut s = "<g".to_owned();
+        if let Some(t) = transform_pix {
+            s += &format!(
+                " transform=\"matrix({} {} {} {} {} {})\"",
+                t[0], t[1], t[3], t[4], t[6], t[7]
+            );
+        }
+        if let Some(opacity) = opacity {
+            s += &format!(" opacity=\"{}\"", opacity);
+        }
+        s += ">";
+        self.tags.push(s);
+    }
+
+    /// close the group started by `begin_group`
+    pub fn end_group(&mut self) {
+        self.tags.push("</g>".to_owned());
+    }
+}
+
+impl crate::canvas::Draw for Canvas {
+    /// remove all the elements and put the background rectangle
+    fn clear(&mut self, color: Rgba<u8>) {
+        self.tags.clear();
+        self.defs.clear();
+        self.tags.push(format!(
+            "<rect x=\"0\" y=\"0\" width=\"{}\" height=\"{}\" fill=\"{}\" />",
+            self.width,
+            self.height,
+            color.to_hex_rgb()
+        ));
+    }
+
+    fn line(&mut self, p0: &[f32; 2], p1: &[f32; 2], transform_xy2pix: &[f32; 9], style: &Style) {
+        self.segment(p0, p1, transform_xy2pix, style);
+    }
+
+    fn polyline(&mut self, vtx2xy: &[f32], transform_xy2pix: &[f32; 9], style: &Style) {
+        Canvas::polyline(self, vtx2xy, transform_xy2pix, style);
+    }
+
+    fn polygon(&mut self, vtx2xy: &[f32], transform_xy2pix: &[f32; 9], style: &Style) {
+        Canvas::polygon(self, vtx2xy, transform_xy2pix, style);
+    }
+
+    fn circle(
         &mut self,
-        x1: f32,
-        y1: f32,
-        x2: f32,
-        y2: f32,
+        center: &[f32; 2],
+        radius_pix: f32,
         transform_xy2pix: &[f32; 9],
-        stroke_width: Option<f32>,
+        style: &Style,
     ) {
-        let p1 = [x1, y1, 1.];
-        let q1 = del_geo_core::mat3_col_major::mult_vec(transform_xy2pix, &p1);
-        let p2 = [x2, y2, 1.];
-        let q2 = del_geo_core::mat3_col_major::mult_vec(transform_xy2pix, &p2);
+        let q =
+            del_geo_core::mat3_col_major::transform_homogeneous(transform_xy2pix, center).unwrap();
         let s = format!(
-            "<line x1=\"{}\" y1=\"{}\" x2=\"{}\" y2=\"{}\" stroke=\"black\" {} />",
-            q1[0] / q1[2],
-            q1[1] / q1[2],
-            q2[0] / q2[2],
-            q2[1] / q2[2],
-            if stroke_width.is_some() {
-                format!("stroke-width=\"{}\"", stroke_width.unwrap())
-            } else {
-                "".to_owned()
-            }
+            "<circle cx=\"{}\" cy=\"{}\" r=\"{}\"{} />",
+            q[0],
+            q[1],
+            radius_pix,
+            self.style_attributes(style)
         );
         self.tags.push(s);
     }
 }
 
+/// the whole SVG document. Use `to_string()` to get it as `String`
+impl std::fmt::Display for Canvas {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "<svg width=\"{}\" height=\"{}\" xmlns=\"http://www.w3.org/2000/svg\">",
+            self.width, self.height
+        )?;
+        if !self.defs.is_empty() {
+            writeln!(f, "<defs>")?;
+            for s in &self.defs {
+                writeln!(f, "{}", s)?;
+            }
+            writeln!(f, "</defs>")?;
+        }
+        for s in &self.tags {
+            writeln!(f, "{}", s)?;
+        }
+        writeln!(f, "</svg>")
+    }
+}
+
+/// escape the characters with special meanings in XML text and attribute values
+/// (e.g., for tags pushed directly to `Canvas::tags`)
+pub fn escape_xml(s: &str) -> String {
+    let mut res = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '<' => res += "&lt;",
+            '>' => res += "&gt;",
+            '&' => res += "&amp;",
+            '"' => res += "&quot;",
+            '\'' => res += "&apos;",
+            c => res.push(c),
+        }
+    }
+    res
+}
+
 #[test]
 fn hoge() {
     let str2 = "M 457.60409,474.77081 H 347.66161 \
@@ -124,27 +559,22 @@ fn hoge() {
     M 165.13281,263.08599 q 5.77046,0 10.02238,0.30371 4.25192,0 6.9853,0 58.91944,0 88.68288,-25.51151 \
     30.06714,-25.51152 30.06714,-64.99362 0,-38.57098 -24.29668,-62.56395 -23.99297,-24.296679 \
     -63.77879,-24.296679 -17.61509,0 -47.68223,5.770461 z";
-    let strs = svg_outline_path_from_shape(str2);
-    // dbg!(&strs);
-    let loops = svg_loops_from_outline_path(&strs);
-    // dbg!(&loops);
-    // dbg!(loops.len());
+    let path = crate::svg::path::parse(str2).unwrap();
+    let loops = crate::svg::path::to_polybezier_loops(&path);
     let (width, height) = (512usize, 512usize);
     let mut img_data = vec![255u8; height * width];
     // winding number
-    for i_w in 0..width {
-        for i_h in 0..height {
-            let p = [i_w as f32 + 0.5f32, i_h as f32 + 0.5f32];
-            let mut wn = 0.0f32;
-            for i_loop in 0..loops.len() {
-                use slice_of_array::SliceFlatExt;
-                let loop0 = loops[i_loop].0.flat();
-                wn += crate::rasterize::polygon2::winding_number(loop0, &p);
-            }
-            if wn.round() as i64 != 0 {
-                img_data[i_h * width + i_w] = 128;
-            }
-        }
+    {
+        use slice_of_array::SliceFlatExt;
+        let loops: Vec<&[f32]> = loops.iter().map(|l| l.0.flat()).collect();
+        crate::rasterize::polygon2::fill_loops(
+            &mut img_data,
+            width,
+            &loops,
+            &[1., 0., 0., 0., 1., 0., 0., 0., 1.],
+            crate::rasterize::polygon2::FillRule::NonZero,
+            128,
+        );
     }
     // dda
     for (vtx2xy, _seg2vtx, _is_close) in &loops {
@@ -193,13 +623,15 @@ fn hoge1() {
     M 165.13281,263.08599 q 5.77046,0 10.02238,0.30371 4.25192,0 6.9853,0 58.91944,0 88.68288,-25.51151 \
     30.06714,-25.51152 30.06714,-64.99362 0,-38.57098 -24.29668,-62.56395 -23.99297,-24.296679 \
     -63.77879,-24.296679 -17.61509,0 -47.68223,5.770461 z";
-    let strs = svg_outline_path_from_shape(str2);
-    let loops = svg_loops_from_outline_path(&strs);
+    let path = crate::svg::path::parse(str2).unwrap();
+    let loops = crate::svg::path::to_polybezier_loops(&path);
     // dbg!(&loops);
     let (width, height) = (512usize, 512usize);
     let mut img_data = vec![255u8; height * width];
     for (vtx2xy, seg2vtx, is_close) in &loops {
-        let vtxp2xy = polybezier2polyloop(&vtx2xy, &seg2vtx, *is_close, 0.01);
+        let transform_xy2pix = [1., 0., 0., 0., 1., 0., 0., 0., 1.];
+        let vtxp2xy =
+            polybezier2polyloop_adaptive(vtx2xy, seg2vtx, *is_close, &transform_xy2pix, 0.25);
         for i_vtx in 0..vtxp2xy.len() {
             let j_vtx = (i_vtx + 1) % vtxp2xy.len();
             let p0 = vtxp2xy[i_vtx];
@@ -238,204 +670,8 @@ where
     res
 }
 
-pub fn svg_outline_path_from_shape(s0: &str) -> Vec<String> {
-    let s0 = s0.as_bytes();
-    let mut imark = 0;
-    let mut strs = Vec::<String>::new();
-    for i in 0..s0.len() {
-        if s0[i].is_ascii_digit() {
-            continue;
-        }
-        if s0[i] == b',' {
-            strs.push(std::str::from_utf8(&s0[imark..i]).unwrap().to_string());
-            imark = i + 1; // mark should be the beginning position of the string so move next
-        }
-        if s0[i] == b' ' {
-            // sometimes the space act as delimiter in the SVG (inkscape version)
-            if i > imark {
-                strs.push(std::str::from_utf8(&s0[imark..i]).unwrap().to_string());
-            }
-            imark = i + 1; // mark should be the beginning position of the string so move next
-        }
-        if s0[i] == b'-' {
-            if i > imark {
-                strs.push(std::str::from_utf8(&s0[imark..i]).unwrap().to_string());
-            }
-            imark = i;
-        }
-        if s0[i].is_ascii_alphabetic() {
-            if i > imark {
-                strs.push(std::str::from_utf8(&s0[imark..i]).unwrap().to_string());
-            }
-            strs.push(std::str::from_utf8(&[s0[i]]).unwrap().to_string()); // push tag
-            imark = i + 1;
-        }
-    }
-    if s0.len() > imark {
-        strs.push(
-            std::str::from_utf8(&s0[imark..s0.len()])
-                .unwrap()
-                .to_string(),
-        );
-    }
-    strs
-}
-
-#[allow(clippy::identity_op)]
-pub fn svg_loops_from_outline_path(strs: &[String]) -> Vec<(Vec<[f32; 2]>, Vec<usize>, bool)> {
-    use del_geo_core::vec2::Vec2;
-    let hoge = |s0: &str, s1: &str| [s0.parse::<f32>().unwrap(), s1.parse::<f32>().unwrap()];
-    let mut loops: Vec<(Vec<[f32; 2]>, Vec<usize>, bool)> = vec![];
-    let mut vtxl2xy: Vec<[f32; 2]> = vec![];
-    let mut seg2vtxl: Vec<usize> = vec![0];
-    assert!(strs[0] == "M" || strs[0] == "m");
-    // assert!(strs[strs.len() - 1] == "Z" || strs[strs.len() - 1] == "z");
-    let mut pos_cur = [0f32; 2];
-    let mut is = 0;
-    loop {
-        if strs[is] == "M" {
-            // start absolute
-            is += 1;
-            pos_cur = hoge(&strs[is + 0], &strs[is + 1]);
-            vtxl2xy.push(pos_cur);
-            is += 2;
-        } else if strs[is] == "m" {
-            // start relative
-            is += 1;
-            pos_cur = pos_cur.add(&hoge(&strs[is + 0], &strs[is + 1]));
-            vtxl2xy.push(pos_cur);
-            is += 2;
-        } else if strs[is] == "l" {
-            // line relative
-            is += 1;
-            loop {
-                seg2vtxl.push(vtxl2xy.len());
-                let p1 = pos_cur.add(&hoge(&strs[is + 0], &strs[is + 1]));
-                vtxl2xy.push(p1);
-                pos_cur = p1;
-                is += 2;
-                if strs[is].as_bytes()[0].is_ascii_alphabetic() {
-                    break;
-                }
-            }
-        } else if strs[is] == "L" {
-            // line absolute
-            is += 1;
-            loop {
-                seg2vtxl.push(vtxl2xy.len());
-                let p1 = hoge(&strs[is + 0], &strs[is + 1]);
-                vtxl2xy.push(p1);
-                pos_cur = p1;
-                is += 2;
-                if strs[is].as_bytes()[0].is_ascii_alphabetic() {
-                    break;
-                }
-            }
-        } else if strs[is] == "v" {
-            // vertical relative
-            seg2vtxl.push(vtxl2xy.len());
-            let p1 = pos_cur.add(&[0., strs[is + 1].parse::<f32>().unwrap()]);
-            vtxl2xy.push(p1);
-            pos_cur = p1;
-            is += 2;
-        } else if strs[is] == "V" {
-            // vertical absolute
-            seg2vtxl.push(vtxl2xy.len());
-            let p1 = [pos_cur[0], strs[is + 1].parse::<f32>().unwrap()];
-            vtxl2xy.push(p1);
-            pos_cur = p1;
-            is += 2;
-        } else if strs[is] == "H" {
-            // horizontal absolute
-            seg2vtxl.push(vtxl2xy.len());
-            let p1 = [strs[is + 1].parse::<f32>().unwrap(), pos_cur[1]];
-            vtxl2xy.push(p1);
-            pos_cur = p1;
-            is += 2;
-        } else if strs[is] == "h" {
-            // horizontal relative
-            seg2vtxl.push(vtxl2xy.len());
-            let dh = strs[is + 1].parse::<f32>().unwrap();
-            let p1 = pos_cur.add(&[dh, 0.]);
-            vtxl2xy.push(p1);
-            pos_cur = p1;
-            is += 2;
-        } else if strs[is] == "q" {
-            // relative
-            is += 1;
-            loop {
-                // loop for poly quadratic Bézeir curve
-                let pm0 = pos_cur.add(&hoge(&strs[is + 0], &strs[is + 1]));
-                let p1 = pos_cur.add(&hoge(&strs[is + 2], &strs[is + 3]));
-                vtxl2xy.push(pm0);
-                seg2vtxl.push(vtxl2xy.len());
-                vtxl2xy.push(p1);
-                pos_cur = p1;
-                is += 4;
-                if is == strs.len() {
-                    loops.push((vtxl2xy.clone(), seg2vtxl.clone(), false));
-                    break;
-                }
-                if strs[is].as_bytes()[0].is_ascii_alphabetic() {
-                    break;
-                }
-            }
-        } else if strs[is] == "Q" {
-            // absolute
-            is += 1;
-            loop {
-                // loop for poly-Bezeir curve
-                let pm0 = hoge(&strs[is + 0], &strs[is + 1]);
-                let p1 = hoge(&strs[is + 2], &strs[is + 3]);
-                vtxl2xy.push(pm0);
-                seg2vtxl.push(vtxl2xy.len());
-                vtxl2xy.push(p1);
-                pos_cur = p1;
-                is += 4;
-                if strs[is].as_bytes()[0].is_ascii_alphabetic() {
-                    break;
-                }
-            }
-        } else if strs[is] == "c" {
-            // relative
-            is += 1;
-            loop {
-                // loop for poly cubic Bézeir curve
-                let pm0 = pos_cur.add(&hoge(&strs[is + 0], &strs[is + 1]));
-                let pm1 = pos_cur.add(&hoge(&strs[is + 2], &strs[is + 3]));
-                let p1 = pos_cur.add(&hoge(&strs[is + 4], &strs[is + 5]));
-                vtxl2xy.push(pm0);
-                vtxl2xy.push(pm1);
-                seg2vtxl.push(vtxl2xy.len());
-                vtxl2xy.push(p1);
-                pos_cur = p1;
-                is += 6;
-                if is == strs.len() {
-                    loops.push((vtxl2xy.clone(), seg2vtxl.clone(), false));
-                    break;
-                }
-                if strs[is].as_bytes()[0].is_ascii_alphabetic() {
-                    break;
-                }
-            }
-        } else if strs[is] == "z" || strs[is] == "Z" {
-            let pe = vtxl2xy[0];
-            let ps = vtxl2xy[vtxl2xy.len() - 1];
-            let _dist0 = ps.sub(&pe).norm();
-            loops.push((vtxl2xy.clone(), seg2vtxl.clone(), true));
-            vtxl2xy.clear();
-            seg2vtxl = vec![0];
-            is += 1;
-        } else {
-            dbg!("error!--> ", &strs[is]);
-            break;
-        }
-        if is == strs.len() {
-            break;
-        }
-    }
-    loops
-}
+/// polyline of the poly-Bézier loop with the fixed subdivision of the quadratic curves
+#[deprecated(note = "use polybezier2polyloop_adaptive")]
 pub fn polybezier2polyloop(
     vtx2xy: &[[f32; 2]],
     seg2vtx: &[usize],
@@ -498,3 +734,224 @@ pub fn polybezier2polyloop(
     }
     ret
 }
+
+/// flatten the poly-Bézier loop into a polyline such that the distance between the curve
+/// and the polyline is less than `tolerance` in the **pixel** coordinate.
+/// Small curves get only a few vertices and large curves get as many as they need.
+///
+/// The output is in the same coordinate as `vtx2xy`. The last vertex of an open loop is included.
+/// * `transform_xy2pix` - 3x3 homogeneous transformation matrix with **column major** order
+pub fn polybezier2polyloop_adaptive(
+    vtx2xy: &[[f32; 2]],
+    seg2vtx: &[usize],
+    is_close: bool,
+    transform_xy2pix: &[f32; 9],
+    tolerance: f32,
+) -> Vec<[f32; 2]> {
+    let mut ret: Vec<[f32; 2]> = vec![];
+    let num_seg = seg2vtx.len() - 1;
+    for i_seg in 0..num_seg {
+        let (is_vtx, ie_vtx) = (seg2vtx[i_seg], seg2vtx[i_seg + 1]);
+        ret.push(vtx2xy[is_vtx]);
+        flatten_bezier_adaptive(
+            &vtx2xy[is_vtx..ie_vtx + 1],
+            transform_xy2pix,
+            tolerance,
+            0,
+            &mut ret,
+        );
+    }
+    let p_last = vtx2xy[seg2vtx[num_seg]];
+    if !is_close || p_last != vtx2xy[0] {
+        ret.push(p_last);
+    }
+    ret
+}
+
+/// push the interior points of the Bézier curve (the end points are excluded) subdividing it
+/// recursively until the control points are close enough to the chord in the pixel coordinate.
+/// The curve is at most cubic (i.e., `ctrl.len() <= 4`)
+fn flatten_bezier_adaptive(
+    ctrl: &[[f32; 2]],
+    transform_xy2pix: &[f32; 9],
+    tolerance: f32,
+    depth: usize,
+    ret: &mut Vec<[f32; 2]>,
+) {
+    use del_geo_core::vec2::Vec2;
+    let n = ctrl.len();
+    assert!(n <= 4, "only up to the cubic Bézier curve is supported");
+    if n <= 2 {
+        return; // the straight line needs no subdivision
+    }
+    let mut ctrl_pix = [[0f32; 2]; 4];
+    for (q, p) in ctrl_pix.iter_mut().zip(ctrl.iter()) {
+        *q = del_geo_core::mat3_col_major::transform_homogeneous(transform_xy2pix, p).unwrap();
+    }
+    let (ps, pe) = (&ctrl_pix[0], &ctrl_pix[n - 1]);
+    let dist_to_chord = |p: &[f32; 2]| {
+        let (_, q) = del_geo_core::edge2::nearest_to_point(ps, pe, p);
+        q.sub(p).norm()
+    };
+    // the curve lies in the convex hull of the control points.
+    // The quadratic curve deviates at most half of the distance of its control point
+    let deviation = if n == 3 {
+        dist_to_chord(&ctrl_pix[1]) * 0.5
+    } else {
+        ctrl_pix[1..n - 1]
+            .iter()
+            .map(dist_to_chord)
+            .fold(0f32, f32::max)
+    };
+    if deviation <= tolerance || depth >= 16 {
+        return;
+    }
+    // de Casteljau subdivision at the middle
+    let mut work = [[0f32; 2]; 4];
+    work[..n].copy_from_slice(ctrl);
+    let mut left = [[0f32; 2]; 4];
+    let mut right = [[0f32; 2]; 4];
+    for k in 0..n {
+        left[k] = work[0];
+        right[n - 1 - k] = work[n - 1 - k];
+        for i in 0..n - 1 - k {
+            work[i] = work[i].add(&work[i + 1]).scale(0.5);
+        }
+    }
+    flatten_bezier_adaptive(&left[..n], transform_xy2pix, tolerance, depth + 1, ret);
+    ret.push(right[0]);
+    flatten_bezier_adaptive(&right[..n], transform_xy2pix, tolerance, depth + 1, ret);
+}
+
+#[test]
+fn test_polybezier2polyloop_adaptive() {
+    // open loop made of a cubic Bézier curve and a line segment
+    let vtx2xy = [[0f32, 0.], [0.3, 1.], [0.7, 1.], [1., 0.], [0.5, -0.5]];
+    let seg2vtx = [0usize, 3, 4];
+    use del_geo_core::vec2::Vec2;
+    let tolerance = 0.25;
+    let mut num_vtx = vec![];
+    for scale in [10f32, 100., 1000.] {
+        let transform = [scale, 0., 0., 0., -scale, 0., 0., scale, 1.];
+        let polyline =
+            polybezier2polyloop_adaptive(&vtx2xy, &seg2vtx, false, &transform, tolerance);
+        assert_eq!(polyline[0], vtx2xy[0]);
+        assert_eq!(*polyline.last().unwrap(), vtx2xy[4]);
+        let to_pix = |p: &[f32; 2]| {
+            del_geo_core::mat3_col_major::transform_homogeneous(&transform, p).unwrap()
+        };
+        // the samples on the cubic curve are close to the polyline in the pixel coordinate
+        for i in 0..=100 {
+            let t = i as f32 / 100.;
+            let p =
+                del_geo_core::bezier_cubic::eval(&vtx2xy[0], &vtx2xy[1], &vtx2xy[2], &vtx2xy[3], t);
+            let p = to_pix(&p);
+            let dist = polyline
+                .windows(2)
+                .map(|w| {
+                    let (_, q) =
+                        del_geo_core::edge2::nearest_to_point(&to_pix(&w[0]), &to_pix(&w[1]), &p);
+                    q.sub(&p).norm()
+                })
+                .fold(f32::MAX, f32::min);
+            assert!(dist < tolerance * 1.01, "{} {}", scale, dist);
+        }
+        num_vtx.push(polyline.len());
+    }
+    assert!(
+        num_vtx[0] < num_vtx[1] && num_vtx[1] < num_vtx[2],
+        "{:?}",
+        num_vtx
+    );
+}
+
+#[test]
+fn test_styled_elements() -> anyhow::Result<()> {
+    let mut canvas = Canvas::new("target/canvas_svg_styled.svg".to_string(), (300, 200));
+    let transform_xy2pix = [100f32, 0., 0., 0., -100., 0., 150., 100., 1.];
+    let style_red = Style {
+        stroke: Some(Rgba::from(0xff0000)),
+        stroke_width: Some(3.),
+        dash: vec![6., 3.],
+        line_cap: Some(LineCap::Round),
+        line_join: Some(LineJoin::Bevel),
+        ..Default::default()
+    };
+    canvas.polyline(
+        &[-1.2, -0.8, -0.8, 0.8, -0.4, -0.8],
+        &transform_xy2pix,
+        &style_red,
+    );
+    let style_arrow = Style {
+        arrow_end: true,
+        ..Default::default()
+    };
+    // the `Draw` methods can be called with the method-call syntax
+    use crate::canvas::Draw;
+    canvas.line(&[0., 0.], &[0.5, 0.5], &transform_xy2pix, &style_arrow);
+    canvas.line_xy(0., 0., 0.5, -0.5, &transform_xy2pix, &style_arrow);
+    canvas.begin_group(None, Some(0.5));
+    let style_fill = Style {
+        fill: Some(Rgba::from(0x00ff00)),
+        stroke: None,
+        ..Default::default()
+    };
+    canvas.rect(&[0.6, -0.9, 1.4, -0.1], &transform_xy2pix, &style_fill);
+    canvas.ellipse(1.0, 0.5, 0.4, 0.2, &transform_xy2pix, &style_fill);
+    canvas.end_group();
+    canvas.cubic_bezier(
+        &[[-1., 0.9], [-0.5, 0.2], [0.5, 1.6], [1., 0.9]],
+        &transform_xy2pix,
+        &Style::default(),
+    );
+    canvas.text(
+        0.,
+        -0.9,
+        &transform_xy2pix,
+        "a < b & c",
+        12.,
+        Rgba::from(0x0000ff),
+        TextAnchor::Middle,
+    );
+    assert!(canvas.tags[0].contains("stroke-dasharray=\"6 3\""));
+    assert!(canvas.tags[1].contains("marker-end=\"url(#arrow-000000)\""));
+    assert_eq!(canvas.defs.len(), 1); // the marker is shared
+    assert!(canvas
+        .tags
+        .last()
+        .unwrap()
+        .contains(">a &lt; b &amp; c</text>"));
+    canvas.write()?;
+    // the output can be read by our own parser
+    let doc = crate::svg::document::Document::load("target/canvas_svg_styled.svg")?;
+    assert_eq!(doc.shapes.len(), 6);
+    assert_eq!(doc.shapes[3].style.opacity, 0.5);
+    Ok(())
+}
+
+#[test]
+fn test_write_to() -> anyhow::Result<()> {
+    let mut canvas = Canvas::new("".to_string(), (10, 20));
+    let identity = [1f32, 0., 0., 0., 1., 0., 0., 0., 1.];
+    canvas.circle_rgba(1., 2., &identity, 3., Rgba::new(255, 0, 0, 128));
+    canvas.text(
+        4.,
+        5.,
+        &identity,
+        "x<y",
+        8.,
+        Rgba::from(0x123456),
+        TextAnchor::Start,
+    );
+    let mut buf = Vec::<u8>::new();
+    canvas.write_to(&mut buf)?;
+    let expected = "<svg width=\"10\" height=\"20\" xmlns=\"http://www.w3.org/2000/svg\">\n\
+        <circle cx=\"1\" cy=\"2\" r=\"3\" stroke=\"none\" fill=\"#FF0000\" fill-opacity=\"0.5019608\" />\n\
+        <text x=\"4\" y=\"5\" font-size=\"8\" fill=\"#123456\" text-anchor=\"start\">x&lt;y</text>\n\
+        </svg>\n";
+    assert_eq!(String::from_utf8(buf)?, expected);
+    assert_eq!(canvas.to_string(), expected);
+    // the file cannot be created at the empty path
+    assert!(canvas.write().is_err());
+    Ok(())
+}
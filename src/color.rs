@@ -24,12 +24,12 @@ pub fn i32_from_f32rgb(r: f32, g: f32, b: f32) -> i32 {
     r * 0x010000 + g * 0x000100 + b
 }
 
+/// * `h` - hue in `[0, 1]`. The value outside is wrapped around (e.g., `1.0` is the same as `0.0`)
 pub fn rgb_from_hsv<T>(h: T, s: T, v: T) -> (T, T, T)
 where
     T: num_traits::Float + std::ops::MulAssign + AsPrimitive<i32>,
     i32: AsPrimitive<T>,
 {
-    assert!(h >= T::zero() && h <= T::one());
     if s < T::zero() {
         return (v, v, v);
     }
@@ -37,7 +37,7 @@ where
     let one = T::one();
     let six = one + one + one + one + one + one;
     let (mut r, mut g, mut b) = (v, v, v);
-    let h = h * six;
+    let h = (h - h.floor()) * six;
     let i: i32 = h.as_();
     let f: T = h - i.as_();
     match i {
@@ -61,11 +61,578 @@ where
             r *= one - s * (one - f);
             g *= one - s;
         }
-        5 => {
+        _ => {
+            // `i == 6` may happen for `h` slightly less than one due to the rounding
             g *= one - s;
             b *= one - s * f;
         }
-        _ => panic!(),
     }
     (r, g, b)
 }
+
+fn cst<T: num_traits::Float>(v: f64) -> T {
+    T::from(v).unwrap()
+}
+
+/// hue in `[0, 1)` and the chroma of the RGB
+fn hue_chroma<T: num_traits::Float>(r: T, g: T, b: T) -> (T, T) {
+    let max = r.max(g).max(b);
+    let min = r.min(g).min(b);
+    let c = max - min;
+    if c <= T::zero() {
+        return (T::zero(), c);
+    }
+    let h = if max == r {
+        (g - b) / c
+    } else if max == g {
+        (b - r) / c + cst(2.)
+    } else {
+        (r - g) / c + cst(4.)
+    };
+    let h = h / cst(6.);
+    (h - h.floor(), c)
+}
+
+/// inverse of `rgb_from_hsv`. The hue is in `[0, 1)` and zero for the gray
+pub fn hsv_from_rgb<T: num_traits::Float>(r: T, g: T, b: T) -> (T, T, T) {
+    let (h, c) = hue_chroma(r, g, b);
+    let v = r.max(g).max(b);
+    let s = if v > T::zero() { c / v } else { T::zero() };
+    (h, s, v)
+}
+
+/// * `h` - hue in `[0, 1]`. The value outside is wrapped around. NaN and infinity are zero
+pub fn rgb_from_hsl<T: num_traits::Float>(h: T, s: T, l: T) -> (T, T, T) {
+    let one = T::one();
+    let c = (one - (cst::<T>(2.) * l - one).abs()) * s;
+    let h = if h.is_finite() { h } else { T::zero() };
+    let h6 = (h - h.floor()) * cst(6.);
+    let x = c * (one - ((h6 % cst(2.)) - one).abs());
+    let (r, g, b) = match h6.to_i32().unwrap_or(0) {
+        0 => (c, x, T::zero()),
+        1 => (x, c, T::zero()),
+        2 => (T::zero(), c, x),
+        3 => (T::zero(), x, c),
+        4 => (x, T::zero(), c),
+        _ => (c, T::zero(), x),
+    };
+    let m = l - c * cst(0.5);
+    (r + m, g + m, b + m)
+}
+
+/// inverse of `rgb_from_hsl`. The hue is in `[0, 1)` and zero for the gray
+pub fn hsl_from_rgb<T: num_traits::Float>(r: T, g: T, b: T) -> (T, T, T) {
+    let (h, c) = hue_chroma(r, g, b);
+    let one = T::one();
+    let l = (r.max(g).max(b) + r.min(g).min(b)) * cst(0.5);
+    let s = if l <= T::zero() || l >= one {
+        T::zero()
+    } else {
+        c / (one - (cst::<T>(2.) * l - one).abs())
+    };
+    (h, s, l)
+}
+
+/// decode the sRGB gamma. The input and output are in `[0, 1]`
+pub fn linear_from_srgb<T: num_traits::Float>(c: T) -> T {
+    if c <= cst(0.04045) {
+        c / cst(12.92)
+    } else {
+        ((c + cst(0.055)) / cst(1.055)).powf(cst(2.4))
+    }
+}
+
+/// encode the linear intensity with the sRGB gamma. The input and output are in `[0, 1]`
+pub fn srgb_from_linear<T: num_traits::Float>(c: T) -> T {
+    if c <= cst(0.0031308) {
+        c * cst(12.92)
+    } else {
+        cst::<T>(1.055) * c.powf(cst(1. / 2.4)) - cst(0.055)
+    }
+}
+
+/// CIE XYZ from the linear RGB with the sRGB primaries and the D65 white point (`Y=1` for white)
+pub fn xyz_from_linear_rgb<T: num_traits::Float>(r: T, g: T, b: T) -> (T, T, T) {
+    let x = cst::<T>(0.4124564) * r + cst::<T>(0.3575761) * g + cst::<T>(0.1804375) * b;
+    let y = cst::<T>(0.2126729) * r + cst::<T>(0.7151522) * g + cst::<T>(0.0721750) * b;
+    let z = cst::<T>(0.0193339) * r + cst::<T>(0.1191920) * g + cst::<T>(0.9503041) * b;
+    (x, y, z)
+}
+
+/// inverse of `xyz_from_linear_rgb`. The output may be out of `[0, 1]` outside the sRGB gamut
+pub fn linear_rgb_from_xyz<T: num_traits::Float>(x: T, y: T, z: T) -> (T, T, T) {
+    let r = cst::<T>(3.2404542) * x - cst::<T>(1.5371385) * y - cst::<T>(0.4985314) * z;
+    let g = cst::<T>(-0.9692660) * x + cst::<T>(1.8760108) * y + cst::<T>(0.0415560) * z;
+    let b = cst::<T>(0.0556434) * x - cst::<T>(0.2040259) * y + cst::<T>(1.0572252) * z;
+    (r, g, b)
+}
+
+/// white point D65
+const XYZ_WHITE: [f64; 3] = [0.95047, 1.0, 1.08883];
+
+/// CIE L*a*b* from CIE XYZ with the D65 white point. `L` is in `[0, 100]`
+pub fn lab_from_xyz<T: num_traits::Float>(x: T, y: T, z: T) -> (T, T, T) {
+    let delta: T = cst(6. / 29.);
+    let f = |t: T| {
+        if t > delta * delta * delta {
+            t.cbrt()
+        } else {
+            t / (cst::<T>(3.) * delta * delta) + cst(4. / 29.)
+        }
+    };
+    let fx = f(x / cst(XYZ_WHITE[0]));
+    let fy = f(y / cst(XYZ_WHITE[1]));
+    let fz = f(z / cst(XYZ_WHITE[2]));
+    (
+        cst::<T>(116.) * fy - cst(16.),
+        cst::<T>(500.) * (fx - fy),
+        cst::<T>(200.) * (fy - fz),
+    )
+}
+
+/// inverse of `lab_from_xyz`
+pub fn xyz_from_lab<T: num_traits::Float>(l: T, a: T, b: T) -> (T, T, T) {
+    let delta: T = cst(6. / 29.);
+    let finv = |t: T| {
+        if t > delta {
+            t * t * t
+        } else {
+            cst::<T>(3.) * delta * delta * (t - cst(4. / 29.))
+        }
+    };
+    let fy = (l + cst(16.)) / cst(116.);
+    let fx = fy + a / cst(500.);
+    let fz = fy - b / cst(200.);
+    (
+        finv(fx) * cst(XYZ_WHITE[0]),
+        finv(fy) * cst(XYZ_WHITE[1]),
+        finv(fz) * cst(XYZ_WHITE[2]),
+    )
+}
+
+/// CIE L*a*b* from the (gamma encoded) sRGB in `[0, 1]`
+pub fn lab_from_srgb<T: num_traits::Float>(r: T, g: T, b: T) -> (T, T, T) {
+    let (x, y, z) = xyz_from_linear_rgb(
+        linear_from_srgb(r),
+        linear_from_srgb(g),
+        linear_from_srgb(b),
+    );
+    lab_from_xyz(x, y, z)
+}
+
+/// inverse of `lab_from_srgb`. The output is clamped into `[0, 1]`
+pub fn srgb_from_lab<T: num_traits::Float>(l: T, a: T, b: T) -> (T, T, T) {
+    let (x, y, z) = xyz_from_lab(l, a, b);
+    let (r, g, b) = linear_rgb_from_xyz(x, y, z);
+    let f = |c: T| srgb_from_linear(c.max(T::zero()).min(T::one()));
+    (f(r), f(g), f(b))
+}
+
+/// cylindrical representation of L*a*b*. The hue is in degrees in `[0, 360)`
+pub fn lch_from_lab<T: num_traits::Float>(l: T, a: T, b: T) -> (T, T, T) {
+    let h = b.atan2(a).to_degrees();
+    let h = if h < T::zero() { h + cst(360.) } else { h };
+    (l, a.hypot(b), h)
+}
+
+/// inverse of `lch_from_lab`
+pub fn lab_from_lch<T: num_traits::Float>(l: T, c: T, h: T) -> (T, T, T) {
+    let (sin, cos) = h.to_radians().sin_cos();
+    (l, c * cos, c * sin)
+}
+
+/// color difference CIE76, i.e., the Euclidean distance in L*a*b*
+pub fn delta_e76<T: num_traits::Float>(lab0: (T, T, T), lab1: (T, T, T)) -> T {
+    let (dl, da, db) = (lab0.0 - lab1.0, lab0.1 - lab1.1, lab0.2 - lab1.2);
+    (dl * dl + da * da + db * db).sqrt()
+}
+
+/// OKLab (Björn Ottosson, 2020) from the linear RGB with the sRGB primaries. `L` is in `[0, 1]`
+pub fn oklab_from_linear_rgb<T: num_traits::Float>(r: T, g: T, b: T) -> (T, T, T) {
+    let l = cst::<T>(0.4122214708) * r + cst::<T>(0.5363325363) * g + cst::<T>(0.0514459929) * b;
+    let m = cst::<T>(0.2119034982) * r + cst::<T>(0.6806995451) * g + cst::<T>(0.1073969566) * b;
+    let s = cst::<T>(0.0883024619) * r + cst::<T>(0.2817188376) * g + cst::<T>(0.6299787005) * b;
+    let (l, m, s) = (l.cbrt(), m.cbrt(), s.cbrt());
+    (
+        cst::<T>(0.2104542553) * l + cst::<T>(0.7936177850) * m - cst::<T>(0.0040720468) * s,
+        cst::<T>(1.9779984951) * l - cst::<T>(2.4285922050) * m + cst::<T>(0.4505937099) * s,
+        cst::<T>(0.0259040371) * l + cst::<T>(0.7827717662) * m - cst::<T>(0.8086757660) * s,
+    )
+}
+
+/// inverse of `oklab_from_linear_rgb`
+pub fn linear_rgb_from_oklab<T: num_traits::Float>(l: T, a: T, b: T) -> (T, T, T) {
+    let l_ = l + cst::<T>(0.3963377774) * a + cst::<T>(0.2158037573) * b;
+    let m_ = l - cst::<T>(0.1055613458) * a - cst::<T>(0.0638541728) * b;
+    let s_ = l - cst::<T>(0.0894841775) * a - cst::<T>(1.2914855480) * b;
+    let (l, m, s) = (l_ * l_ * l_, m_ * m_ * m_, s_ * s_ * s_);
+    (
+        cst::<T>(4.0767416621) * l - cst::<T>(3.3077115913) * m + cst::<T>(0.2309699292) * s,
+        cst::<T>(-1.2684380046) * l + cst::<T>(2.6097574011) * m - cst::<T>(0.3413193965) * s,
+        cst::<T>(-0.0041960863) * l - cst::<T>(0.7034186147) * m + cst::<T>(1.7076147010) * s,
+    )
+}
+
+#[test]
+fn test_color_space() {
+    let near = |a: (f64, f64, f64), b: (f64, f64, f64), eps: f64| {
+        (a.0 - b.0).abs() < eps && (a.1 - b.1).abs() < eps && (a.2 - b.2).abs() < eps
+    };
+    // hue at the boundary does not panic
+    assert_eq!(rgb_from_hsv(1.0f32, 1., 1.), (1., 0., 0.));
+    assert_eq!(hsv_from_rgb(1.0f32, 0., 0.), (0., 1., 1.));
+    assert_eq!(hsl_from_rgb(0.0f32, 0., 1.), (2. / 3., 1., 0.5));
+    // non-finite hue does not panic
+    for h in [f32::NAN, f32::INFINITY, f32::NEG_INFINITY] {
+        assert_eq!(rgb_from_hsl(h, 1., 0.5), (1., 0., 0.));
+    }
+    // known values
+    assert!(near(lab_from_srgb(1., 1., 1.), (100., 0., 0.), 1.0e-3));
+    assert!(near(
+        lab_from_srgb(1., 0., 0.),
+        (53.24, 80.09, 67.20),
+        1.0e-2
+    ));
+    assert!(near(
+        oklab_from_linear_rgb(1., 1., 1.),
+        (1., 0., 0.),
+        1.0e-4
+    ));
+    assert!(near(
+        oklab_from_linear_rgb(1., 0., 0.),
+        (0.62796, 0.22486, 0.12585),
+        1.0e-4
+    ));
+    assert!((srgb_from_linear(linear_from_srgb(0.5f64)) - 0.5).abs() < 1.0e-10);
+    // round trips
+    let mut rng: u64 = 0;
+    let mut rand = || {
+        rng = rng
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        (rng >> 11) as f64 / (1u64 << 53) as f64
+    };
+    for _ in 0..100 {
+        let rgb = (rand(), rand(), rand());
+        let (h, s, v) = hsv_from_rgb(rgb.0, rgb.1, rgb.2);
+        assert!(near(rgb_from_hsv(h, s, v), rgb, 1.0e-10));
+        let (h, s, l) = hsl_from_rgb(rgb.0, rgb.1, rgb.2);
+        assert!(near(rgb_from_hsl(h, s, l), rgb, 1.0e-10));
+        let (l, a, b) = lab_from_srgb(rgb.0, rgb.1, rgb.2);
+        assert!(near(srgb_from_lab(l, a, b), rgb, 1.0e-6));
+        let (l, c, h) = lch_from_lab(l, a, b);
+        assert!(near(
+            lab_from_lch(l, c, h),
+            lab_from_srgb(rgb.0, rgb.1, rgb.2),
+            1.0e-10
+        ));
+        let (l, a, b) = oklab_from_linear_rgb(rgb.0, rgb.1, rgb.2);
+        assert!(near(linear_rgb_from_oklab(l, a, b), rgb, 1.0e-6));
+    }
+    let lab_red = lab_from_srgb(1f64, 0., 0.);
+    assert_eq!(delta_e76(lab_red, lab_red), 0.);
+}
+
+// ---------------------------
+
+/// color with the straight (i.e., not premultiplied) alpha.
+/// The channel type `T` is either integer (`u8`, `u16`) or float in `[0, 1]` (see `blend::Channel`)
+///
+/// ```
+/// use del_canvas::color::Rgba;
+/// let c: Rgba<u8> = "#ff8000".parse().unwrap();
+/// assert_eq!(c, Rgba::from(0xff8000));
+/// assert_eq!(c.convert::<f32>().g, 128. / 255.);
+/// assert_eq!(Rgba::<u8>::parse_css("rgba(255, 0, 0, 0.5)"), Some(Rgba::new(255, 0, 0, 128)));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Rgba<T> {
+    pub r: T,
+    pub g: T,
+    pub b: T,
+    pub a: T,
+}
+
+impl<T> Rgba<T> {
+    pub const fn new(r: T, g: T, b: T, a: T) -> Self {
+        Rgba { r, g, b, a }
+    }
+}
+
+impl Rgba<u8> {
+    pub const BLACK: Self = Rgba::new(0, 0, 0, 255);
+    pub const WHITE: Self = Rgba::new(255, 255, 255, 255);
+    pub const TRANSPARENT: Self = Rgba::new(0, 0, 0, 0);
+}
+
+impl<T> Rgba<T>
+where
+    T: crate::blend::Channel,
+{
+    /// opaque color
+    pub fn opaque(r: T, g: T, b: T) -> Self {
+        Rgba::new(r, g, b, T::from_normalized(1.))
+    }
+
+    /// opaque color from `0xRRGGBB`
+    pub fn from_i32(color: i32) -> Self {
+        let (r, g, b) = u8rgb_from_i32(color);
+        Rgba::<u8>::opaque(r, g, b).convert()
+    }
+
+    /// `0xRRGGBB` ignoring the alpha
+    pub fn to_i32(&self) -> i32 {
+        let c = self.convert::<u8>();
+        i32_form_u8rgb(c.r, c.g, c.b)
+    }
+
+    /// convert the channel type (e.g., `u8` to `f32`)
+    pub fn convert<U: crate::blend::Channel>(&self) -> Rgba<U> {
+        let f = |v: T| U::from_normalized(v.to_normalized());
+        Rgba::new(f(self.r), f(self.g), f(self.b), f(self.a))
+    }
+
+    /// `#RRGGBB` ignoring the alpha
+    pub fn to_hex_rgb(&self) -> String {
+        format!("#{:06X}", self.to_i32())
+    }
+
+    pub fn rgb(&self) -> [T; 3] {
+        [self.r, self.g, self.b]
+    }
+
+    /// alpha in `[0, 1]`
+    pub fn opacity(&self) -> f32 {
+        self.a.to_normalized() as f32
+    }
+
+    pub fn with_opacity(mut self, opacity: f32) -> Self {
+        self.a = T::from_normalized(opacity as f64);
+        self
+    }
+
+    /// parse the CSS color such as `#f80`, `#ff8800cc`, `rgb(255 136 0 / 50%)`, `rgba(255,136,0,0.5)`
+    /// or the named colors (e.g., `orange`)
+    pub fn parse_css(s: &str) -> Option<Self> {
+        parse_css_u8(s).map(|c| c.convert())
+    }
+
+    /// index of the nearest color in `palette` in terms of the Euclidean distance of RGB
+    pub fn nearest_index(&self, palette: &[Self]) -> Option<usize> {
+        let dist = |c: &Self| {
+            let [r0, g0, b0] = self.rgb().map(|v| v.to_normalized());
+            let [r1, g1, b1] = c.rgb().map(|v| v.to_normalized());
+            (r0 - r1).powi(2) + (g0 - g1).powi(2) + (b0 - b1).powi(2)
+        };
+        palette
+            .iter()
+            .enumerate()
+            .min_by(|(_, c0), (_, c1)| dist(c0).total_cmp(&dist(c1)))
+            .map(|(i, _)| i)
+    }
+}
+
+impl<T> From<i32> for Rgba<T>
+where
+    T: crate::blend::Channel,
+{
+    fn from(color: i32) -> Self {
+        Rgba::from_i32(color)
+    }
+}
+
+impl From<(u8, u8, u8)> for Rgba<u8> {
+    fn from(c: (u8, u8, u8)) -> Self {
+        Rgba::opaque(c.0, c.1, c.2)
+    }
+}
+
+impl<T> From<[T; 3]> for Rgba<T>
+where
+    T: crate::blend::Channel,
+{
+    fn from(c: [T; 3]) -> Self {
+        Rgba::opaque(c[0], c[1], c[2])
+    }
+}
+
+impl<T> From<[T; 4]> for Rgba<T> {
+    fn from(c: [T; 4]) -> Self {
+        let [r, g, b, a] = c;
+        Rgba::new(r, g, b, a)
+    }
+}
+
+impl<T> From<Rgba<T>> for [T; 3] {
+    fn from(c: Rgba<T>) -> Self {
+        [c.r, c.g, c.b]
+    }
+}
+
+impl<T> From<Rgba<T>> for [T; 4] {
+    fn from(c: Rgba<T>) -> Self {
+        [c.r, c.g, c.b, c.a]
+    }
+}
+
+impl<T> std::str::FromStr for Rgba<T>
+where
+    T: crate::blend::Channel,
+{
+    type Err = anyhow::Error;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Rgba::parse_css(s).ok_or_else(|| anyhow::anyhow!("invalid color: {}", s))
+    }
+}
+
+/// the alpha channel is the last one as in `[T; 4]`
+impl<T> crate::blend::Pixel for Rgba<T>
+where
+    T: crate::blend::Channel,
+{
+    const NUM_CHANNEL: usize = 4;
+    const ALPHA: Option<usize> = Some(3);
+    fn channel(&self, i_channel: usize) -> f64 {
+        [self.r, self.g, self.b, self.a][i_channel].to_normalized()
+    }
+    fn set_channel(&mut self, i_channel: usize, v: f64) {
+        let v = T::from_normalized(v);
+        match i_channel {
+            0 => self.r = v,
+            1 => self.g = v,
+            2 => self.b = v,
+            _ => self.a = v,
+        }
+    }
+}
+
+fn parse_css_u8(s: &str) -> Option<Rgba<u8>> {
+    let s = s.trim();
+    if let Some(hex) = s.strip_prefix('#') {
+        if !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+            return None;
+        }
+        let d1 = |i: usize| u8::from_str_radix(&hex[i..i + 1], 16).unwrap() * 17;
+        let d2 = |i: usize| u8::from_str_radix(&hex[i * 2..i * 2 + 2], 16).unwrap();
+        return match hex.len() {
+            3 => Some(Rgba::new(d1(0), d1(1), d1(2), 255)),
+            4 => Some(Rgba::new(d1(0), d1(1), d1(2), d1(3))),
+            6 => Some(Rgba::new(d2(0), d2(1), d2(2), 255)),
+            8 => Some(Rgba::new(d2(0), d2(1), d2(2), d2(3))),
+            _ => None,
+        };
+    }
+    let s = s.to_ascii_lowercase();
+    let args = s
+        .strip_prefix("rgba(")
+        .or_else(|| s.strip_prefix("rgb("))
+        .and_then(|s| s.strip_suffix(')'));
+    if let Some(args) = args {
+        // both `rgb(r, g, b, a)` and `rgb(r g b / a)`
+        let args: Vec<&str> = args
+            .split(|c: char| c == ',' || c == '/' || c.is_ascii_whitespace())
+            .filter(|v| !v.is_empty())
+            .collect();
+        let channel = |v: &str| -> Option<u8> {
+            let v = match v.strip_suffix('%') {
+                Some(v) => v.parse::<f32>().ok()? * 2.55,
+                None => v.parse::<f32>().ok()?,
+            };
+            Some(v.round().clamp(0., 255.) as u8)
+        };
+        let alpha = |v: &str| -> Option<u8> {
+            let v = match v.strip_suffix('%') {
+                Some(v) => v.parse::<f32>().ok()? * 0.01,
+                None => v.parse::<f32>().ok()?,
+            };
+            Some((v * 255.).round().clamp(0., 255.) as u8)
+        };
+        return match args.as_slice() {
+            [r, g, b] => Some(Rgba::new(channel(r)?, channel(g)?, channel(b)?, 255)),
+            [r, g, b, a] => Some(Rgba::new(channel(r)?, channel(g)?, channel(b)?, alpha(a)?)),
+            _ => None,
+        };
+    }
+    let c = match s.as_str() {
+        "transparent" => return Some(Rgba::TRANSPARENT),
+        "black" => 0x000000,
+        "white" => 0xffffff,
+        "red" => 0xff0000,
+        "lime" => 0x00ff00,
+        "green" => 0x008000,
+        "blue" => 0x0000ff,
+        "yellow" => 0xffff00,
+        "cyan" | "aqua" => 0x00ffff,
+        "magenta" | "fuchsia" => 0xff00ff,
+        "gray" | "grey" => 0x808080,
+        "darkgray" | "darkgrey" => 0xa9a9a9,
+        "lightgray" | "lightgrey" => 0xd3d3d3,
+        "silver" => 0xc0c0c0,
+        "maroon" => 0x800000,
+        "olive" => 0x808000,
+        "navy" => 0x000080,
+        "purple" => 0x800080,
+        "teal" => 0x008080,
+        "orange" => 0xffa500,
+        "brown" => 0xa52a2a,
+        "pink" => 0xffc0cb,
+        "gold" => 0xffd700,
+        "indigo" => 0x4b0082,
+        "violet" => 0xee82ee,
+        "skyblue" => 0x87ceeb,
+        "steelblue" => 0x4682b4,
+        "crimson" => 0xdc143c,
+        "coral" => 0xff7f50,
+        "salmon" => 0xfa8072,
+        "khaki" => 0xf0e68c,
+        "turquoise" => 0x40e0d0,
+        "tomato" => 0xff6347,
+        "chocolate" => 0xd2691e,
+        "darkgreen" => 0x006400,
+        "darkblue" => 0x00008b,
+        "darkred" => 0x8b0000,
+        _ => return None,
+    };
+    Some(Rgba::from_i32(c))
+}
+
+#[test]
+fn test_rgba() {
+    assert_eq!(
+        Rgba::<u8>::parse_css("#f80"),
+        Some(Rgba::new(255, 136, 0, 255))
+    );
+    assert_eq!(
+        Rgba::<u8>::parse_css("#ff880080"),
+        Some(Rgba::new(255, 136, 0, 128))
+    );
+    assert_eq!(
+        Rgba::<u8>::parse_css("rgb(255, 50%, 0)"),
+        Some(Rgba::from(0xff8000))
+    );
+    assert_eq!(
+        Rgba::<u8>::parse_css("rgb(255 0 0 / 50%)"),
+        Some(Rgba::new(255, 0, 0, 128))
+    );
+    assert_eq!(Rgba::<u8>::parse_css("Navy"), Some(Rgba::from(0x000080)));
+    assert_eq!(
+        Rgba::<u8>::parse_css("#ff88"),
+        Some(Rgba::new(255, 255, 136, 136))
+    );
+    assert_eq!(Rgba::<u8>::parse_css("#ff888"), None);
+    assert_eq!(Rgba::<u8>::parse_css("rgb(1,2)"), None);
+    assert!("unknown".parse::<Rgba<u8>>().is_err());
+    //
+    let c = Rgba::<f32>::from([1., 0.5, 0.]);
+    assert_eq!(c.convert::<u8>(), Rgba::new(255, 128, 0, 255));
+    assert_eq!(c.to_i32(), 0xff8000);
+    assert_eq!(c.to_hex_rgb(), "#FF8000");
+    assert_eq!(<[u8; 3]>::from(Rgba::<u8>::from((1, 2, 3))), [1, 2, 3]);
+    let palette: Vec<Rgba<u8>> = [0x000000, 0xff0000, 0xffffff].map(Rgba::from).to_vec();
+    assert_eq!(Rgba::from(0xe01010).nearest_index(&palette), Some(1));
+    // blending with the alpha channel
+    use crate::blend::Blend;
+    let mut dst = Rgba::<u8>::WHITE;
+    crate::blend::BlendMode::AlphaOver.blend(&mut dst, Rgba::new(0, 0, 0, 128), 1.0);
+    assert_eq!(dst, Rgba::new(127, 127, 127, 255));
+}
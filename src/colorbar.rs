@@ -0,0 +1,487 @@
+//! colorbar showing a `colormap::Colormap` with the tick marks and the labels.
+//! It can be drawn into the RGB bitmap and into `canvas_svg::Canvas`.
+//!
+//! ```
+//! use del_canvas::colorbar::{Colorbar, Orientation};
+//! let cmap = del_canvas::colormap::colormap_by_name("viridis").unwrap();
+//! let colorbar = Colorbar::new(cmap, (-1.0, 1.0), Orientation::Vertical, [10., 10., 30., 110.]);
+//! let mut img = vec![[255u8; 3]; 100 * 120];
+//! colorbar.draw_bitmap(&mut img, 100);
+//! ```
+
+use crate::color::Rgba;
+use crate::colormap::{Colormap, Normalization, Scale, ValueRange};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Orientation {
+    /// the value increases upward. The labels are on the right
+    Vertical,
+    /// the value increases rightward. The labels are below
+    Horizontal,
+}
+
+#[derive(Debug, Clone)]
+pub struct Colorbar {
+    pub colormap: Colormap,
+    /// values at the both ends of the bar
+    pub range: (f32, f32),
+    pub scale: Scale,
+    pub orientation: Orientation,
+    /// region of the bar `[x_min, y_min, x_max, y_max]` in the pixel coordinate
+    pub aabb: [f32; 4],
+    /// approximate number of the ticks. Ignored if `ticks` is `Some`
+    pub num_ticks: usize,
+    /// values of the ticks given explicitly
+    pub ticks: Option<Vec<f32>>,
+    /// length of the tick marks in pixel
+    pub tick_length: f32,
+    /// color of the frame, tick marks and labels
+    pub color: Rgba<u8>,
+    /// height of the labels in pixel. The bitmap font is scaled by an integer factor
+    pub font_size: f32,
+}
+
+impl Colorbar {
+    pub fn new(
+        colormap: Colormap,
+        range: (f32, f32),
+        orientation: Orientation,
+        aabb: [f32; 4],
+    ) -> Self {
+        Colorbar {
+            colormap,
+            range,
+            scale: Scale::Linear,
+            orientation,
+            aabb,
+            num_ticks: 5,
+            ticks: None,
+            tick_length: 4.,
+            color: Rgba::BLACK,
+            font_size: 7.,
+        }
+    }
+
+    /// the colorbar for the output of `colormap::rgb_image_from_scalar_image`.
+    /// The range returned by that function should be passed as `range`
+    pub fn from_normalization(
+        colormap: Colormap,
+        normalization: &Normalization,
+        range: (f32, f32),
+        orientation: Orientation,
+        aabb: [f32; 4],
+    ) -> Self {
+        Colorbar {
+            scale: normalization.scale,
+            ..Self::new(colormap, range, orientation, aabb)
+        }
+    }
+
+    /// normalized position of the value along the bar
+    fn normalize(&self, v: f32) -> f32 {
+        let (min, max) = self.range;
+        let normalization = Normalization {
+            range: ValueRange::Explicit { min, max },
+            scale: self.scale,
+        };
+        normalization.normalize(v, self.range)
+    }
+
+    /// values of the ticks inside the range
+    pub fn tick_values(&self) -> Vec<f32> {
+        let ticks = match (&self.ticks, self.scale) {
+            (Some(ticks), _) => ticks.clone(),
+            (None, Scale::Log) => log_ticks(self.range.0, self.range.1, self.num_ticks),
+            (None, _) => nice_ticks(self.range.0, self.range.1, self.num_ticks),
+        };
+        ticks
+            .into_iter()
+            .filter(|&v| {
+                let t = self.normalize(v);
+                (-1.0e-4..=1. + 1.0e-4).contains(&t)
+            })
+            .collect()
+    }
+
+    /// labels of the ticks with the number of digits enough to distinguish them
+    pub fn tick_labels(&self, ticks: &[f32]) -> Vec<String> {
+        let step = ticks
+            .windows(2)
+            .map(|w| (w[1] - w[0]).abs())
+            .filter(|&d| d > 0.)
+            .reduce(f32::min);
+        ticks
+            .iter()
+            .map(|&v| match (self.scale, step) {
+                (Scale::Log, _) => format_log_tick(v),
+                (_, Some(step)) => format_tick(v, step),
+                (_, None) => format_tick(v, v.abs()),
+            })
+            .collect()
+    }
+
+    /// pixel coordinate of the tick on the side of the bar where the labels are put
+    fn tick_position(&self, v: f32) -> [f32; 2] {
+        let t = self.normalize(v).clamp(0., 1.);
+        let [x0, y0, x1, y1] = self.aabb;
+        match self.orientation {
+            Orientation::Vertical => [x1, y1 - t * (y1 - y0)],
+            Orientation::Horizontal => [x0 + t * (x1 - x0), y1],
+        }
+    }
+
+    /// draw the colorbar into the RGB image
+    pub fn draw_bitmap(&self, img_data: &mut [[u8; 3]], width: usize) {
+        let color = self.color.rgb();
+        let [x0, y0, x1, y1] = self.aabb.map(|v| v.round() as i64);
+        for iy in y0..y1 {
+            for ix in x0..x1 {
+                let t = match self.orientation {
+                    Orientation::Vertical => ((y1 - iy) as f32 - 0.5) / (y1 - y0) as f32,
+                    Orientation::Horizontal => ((ix - x0) as f32 + 0.5) / (x1 - x0) as f32,
+                };
+                let c = self.colormap.eval(t);
+                put_pixel(
+                    img_data,
+                    width,
+                    ix,
+                    iy,
+                    c.map(|v| (v * 255.).round().clamp(0., 255.) as u8),
+                );
+            }
+        }
+        // frame just outside the bar
+        for ix in x0 - 1..=x1 {
+            put_pixel(img_data, width, ix, y0 - 1, color);
+            put_pixel(img_data, width, ix, y1, color);
+        }
+        for iy in y0 - 1..=y1 {
+            put_pixel(img_data, width, x0 - 1, iy, color);
+            put_pixel(img_data, width, x1, iy, color);
+        }
+        //
+        let font_scale = ((self.font_size / 7.).round() as usize).max(1);
+        let tick_length = self.tick_length.round() as i64;
+        let ticks = self.tick_values();
+        let labels = self.tick_labels(&ticks);
+        for (&v, label) in ticks.iter().zip(labels.iter()) {
+            let [px, py] = self.tick_position(v);
+            let label_width = text_width_5x7(label, font_scale) as i64;
+            let label_height = 7 * font_scale as i64;
+            let origin = match self.orientation {
+                Orientation::Vertical => {
+                    let iy = (py.round() as i64).min(y1 - 1);
+                    for ix in x1..x1 + tick_length {
+                        put_pixel(img_data, width, ix, iy, color);
+                    }
+                    (x1 + tick_length + 2, iy - label_height / 2)
+                }
+                Orientation::Horizontal => {
+                    let ix = (px.round() as i64).min(x1 - 1);
+                    for iy in y1..y1 + tick_length {
+                        put_pixel(img_data, width, ix, iy, color);
+                    }
+                    (ix - label_width / 2, y1 + tick_length + 2)
+                }
+            };
+            draw_text_5x7(img_data, width, origin, label, font_scale, color);
+        }
+    }
+
+    /// draw the colorbar into the SVG canvas. The gradient is put in the `<defs>`
+    pub fn draw_svg(&self, canvas: &mut crate::canvas_svg::Canvas) {
+        use crate::canvas_svg::{Style, TextAnchor};
+        let direction = match self.orientation {
+            Orientation::Vertical => "x1=\"0\" y1=\"1\" x2=\"0\" y2=\"0\"",
+            Orientation::Horizontal => "x1=\"0\" y1=\"0\" x2=\"1\" y2=\"0\"",
+        };
+        let hex = |c: [f32; 3]| Rgba::<f32>::from(c).convert::<u8>().to_hex_rgb();
+        let stops: Vec<(f32, [f32; 3])> = match self.colormap.num_bins {
+            // hard steps at the boundaries of the bins
+            Some(num_bins) if num_bins > 1 => (0..num_bins)
+                .flat_map(|i_bin| {
+                    let c = self.colormap.eval((i_bin as f32 + 0.5) / num_bins as f32);
+                    let (t0, t1) = (i_bin as f32, (i_bin + 1) as f32);
+                    [(t0 / num_bins as f32, c), (t1 / num_bins as f32, c)]
+                })
+                .collect(),
+            _ => {
+                let num_stop = 64;
+                (0..num_stop)
+                    .map(|i| {
+                        let t = i as f32 / (num_stop - 1) as f32;
+                        (t, self.colormap.eval(t))
+                    })
+                    .collect()
+            }
+        };
+        let stops: String = stops
+            .iter()
+            .map(|&(t, c)| format!("<stop offset=\"{}\" stop-color=\"{}\" />", t, hex(c)))
+            .collect();
+        let id = canvas.add_def(&format!(
+            "<linearGradient {}>{}</linearGradient>",
+            direction, stops
+        ));
+        let identity = [1., 0., 0., 0., 1., 0., 0., 0., 1.];
+        let style = Style {
+            stroke: Some(self.color),
+            stroke_width: Some(1.),
+            fill: None,
+            ..Default::default()
+        };
+        let style_bar = Style {
+            stroke: None,
+            ..Default::default()
+        };
+        canvas.rect_with_paint(&self.aabb, &identity, &id, &style_bar);
+        canvas.rect(&self.aabb, &identity, &style);
+        //
+        let ticks = self.tick_values();
+        let labels = self.tick_labels(&ticks);
+        for (&v, label) in ticks.iter().zip(labels.iter()) {
+            let [px, py] = self.tick_position(v);
+            match self.orientation {
+                Orientation::Vertical => {
+                    canvas.segment(&[px, py], &[px + self.tick_length, py], &identity, &style);
+                    canvas.text(
+                        px + self.tick_length + 2.,
+                        py + self.font_size * 0.35,
+                        &identity,
+                        label,
+                        self.font_size,
+                        self.color,
+                        TextAnchor::Start,
+                    );
+                }
+                Orientation::Horizontal => {
+                    canvas.segment(&[px, py], &[px, py + self.tick_length], &identity, &style);
+                    canvas.text(
+                        px,
+                        py + self.tick_length + 2. + self.font_size,
+                        &identity,
+                        label,
+                        self.font_size,
+                        self.color,
+                        TextAnchor::Middle,
+                    );
+                }
+            }
+        }
+    }
+}
+
+// ---------------------------
+
+/// "nice" tick values with the step of `1`, `2` or `5` times a power of ten,
+/// such that the number of the ticks in `[min, max]` is close to `num_ticks`
+pub fn nice_ticks(min: f32, max: f32, num_ticks: usize) -> Vec<f32> {
+    let (min, max) = (min.min(max) as f64, min.max(max) as f64);
+    if max <= min || !min.is_finite() || !max.is_finite() {
+        return if min.is_finite() {
+            vec![min as f32]
+        } else {
+            vec![]
+        };
+    }
+    let raw = (max - min) / (num_ticks.max(2) - 1) as f64;
+    let mag = 10f64.powf(raw.log10().floor());
+    let step = [1., 2., 5., 10.]
+        .iter()
+        .map(|&m| m * mag)
+        .min_by(|a, b| (a / raw).ln().abs().total_cmp(&(b / raw).ln().abs()))
+        .unwrap();
+    let i0 = (min / step - 1.0e-6).ceil() as i64;
+    let i1 = (max / step + 1.0e-6).floor() as i64;
+    (i0..=i1).map(|i| (i as f64 * step) as f32).collect()
+}
+
+/// powers of ten in the range. Falls back to `nice_ticks` if the range is narrower than two decades
+fn log_ticks(min: f32, max: f32, num_ticks: usize) -> Vec<f32> {
+    let (min, max) = (min.min(max).max(f32::MIN_POSITIVE), min.max(max));
+    let (e0, e1) = (
+        (min.log10() - 1.0e-4).ceil() as i32,
+        (max.log10() + 1.0e-4).floor() as i32,
+    );
+    if e1 - e0 < 1 {
+        return nice_ticks(min, max, num_ticks);
+    }
+    let stride = ((e1 - e0 + 1) as usize).div_ceil(num_ticks.max(2)).max(1);
+    (e0..=e1).step_by(stride).map(|e| 10f32.powi(e)).collect()
+}
+
+/// label of the linear tick with the decimals enough for the `step`
+fn format_tick(v: f32, step: f32) -> String {
+    let v = if v.abs() < step * 1.0e-4 { 0. } else { v };
+    if v != 0. && (v.abs() >= 1.0e5 || step < 1.0e-4) {
+        return format!("{:e}", v);
+    }
+    let decimals = if step > 0. {
+        (-step.log10().floor()).max(0.) as usize
+    } else {
+        0
+    };
+    format!("{:.*}", decimals, v)
+}
+
+fn format_log_tick(v: f32) -> String {
+    let e = v.log10().round() as i32;
+    if (v - 10f32.powi(e)).abs() > v * 1.0e-4 {
+        return format_tick(v, v);
+    }
+    if (-3..=4).contains(&e) {
+        format!("{:.*}", (-e).max(0) as usize, v)
+    } else {
+        format!("1e{}", e)
+    }
+}
+
+// ---------------------------
+// tiny bitmap font
+
+/// 5x7 glyphs for the numbers. Each row is the five bits from the left
+const GLYPHS_5X7: [(char, [u8; 7]); 14] = [
+    ('0', [0x0e, 0x11, 0x13, 0x15, 0x19, 0x11, 0x0e]),
+    ('1', [0x04, 0x0c, 0x04, 0x04, 0x04, 0x04, 0x0e]),
+    ('2', [0x0e, 0x11, 0x01, 0x02, 0x04, 0x08, 0x1f]),
+    ('3', [0x1f, 0x02, 0x04, 0x02, 0x01, 0x11, 0x0e]),
+    ('4', [0x02, 0x06, 0x0a, 0x12, 0x1f, 0x02, 0x02]),
+    ('5', [0x1f, 0x10, 0x1e, 0x01, 0x01, 0x11, 0x0e]),
+    ('6', [0x06, 0x08, 0x10, 0x1e, 0x11, 0x11, 0x0e]),
+    ('7', [0x1f, 0x01, 0x02, 0x04, 0x08, 0x08, 0x08]),
+    ('8', [0x0e, 0x11, 0x11, 0x0e, 0x11, 0x11, 0x0e]),
+    ('9', [0x0e, 0x11, 0x11, 0x0f, 0x01, 0x02, 0x0c]),
+    ('-', [0x00, 0x00, 0x00, 0x1f, 0x00, 0x00, 0x00]),
+    ('+', [0x00, 0x04, 0x04, 0x1f, 0x04, 0x04, 0x00]),
+    ('.', [0x00, 0x00, 0x00, 0x00, 0x00, 0x0c, 0x0c]),
+    ('e', [0x00, 0x00, 0x0e, 0x11, 0x1f, 0x10, 0x0e]),
+];
+
+/// set the pixel value if `(ix, iy)` is inside the image
+fn put_pixel<VAL>(img_data: &mut [VAL], width: usize, ix: i64, iy: i64, v: VAL) {
+    let height = img_data.len() / width;
+    if ix >= 0 && iy >= 0 && (ix as usize) < width && (iy as usize) < height {
+        img_data[iy as usize * width + ix as usize] = v;
+    }
+}
+
+/// width in pixel of the text drawn by `draw_text_5x7`
+pub fn text_width_5x7(text: &str, scale: usize) -> usize {
+    (text.chars().count() * 6).saturating_sub(1) * scale
+}
+
+/// draw the numeric text (digits, `-`, `+`, `.` and `e`) with the 5x7 bitmap font.
+/// The other characters are drawn as spaces.
+/// * `origin` - top-left corner of the text in pixel. It can be outside the image
+/// * `scale` - the glyphs are magnified by this integer factor
+pub fn draw_text_5x7<VAL: Copy>(
+    img_data: &mut [VAL],
+    width: usize,
+    origin: (i64, i64),
+    text: &str,
+    scale: usize,
+    color: VAL,
+) {
+    let scale = scale as i64;
+    for (i_char, c) in text.chars().enumerate() {
+        let Some((_, glyph)) = GLYPHS_5X7.iter().find(|g| g.0 == c) else {
+            continue;
+        };
+        let x0 = origin.0 + i_char as i64 * 6 * scale;
+        for (i_row, &row) in glyph.iter().enumerate() {
+            for i_col in 0..5 {
+                if (row >> (4 - i_col)) & 1 == 0 {
+                    continue;
+                }
+                for iy in 0..scale {
+                    for ix in 0..scale {
+                        let px = x0 + i_col * scale + ix;
+                        let py = origin.1 + i_row as i64 * scale + iy;
+                        put_pixel(img_data, width, px, py, color);
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn test_ticks() {
+    assert_eq!(nice_ticks(0., 1., 6), vec![0., 0.2, 0.4, 0.6, 0.8, 1.0]);
+    assert_eq!(nice_ticks(-3.7, 12.1, 5), vec![0., 5., 10.]);
+    assert_eq!(
+        log_ticks(0.01, 1000., 6),
+        vec![0.01, 0.1, 1., 10., 100., 1000.]
+    );
+    assert_eq!(format_tick(0.6000001, 0.2), "0.6");
+    assert_eq!(format_tick(-1.0e-9, 0.5), "0.0");
+    assert_eq!(format_tick(150000., 50000.), "1.5e5");
+    assert_eq!(format_log_tick(0.01), "0.01");
+    assert_eq!(format_log_tick(1.0e6), "1e6");
+}
+
+#[test]
+fn test_colorbar() -> anyhow::Result<()> {
+    let img_shape = (200usize, 160usize);
+    let mut img = vec![[255u8; 3]; img_shape.0 * img_shape.1];
+    let cmap = crate::colormap::colormap_by_name("viridis").unwrap();
+    let colorbar = Colorbar::new(
+        cmap.clone(),
+        (-1.0, 1.0),
+        Orientation::Vertical,
+        [20., 20., 40., 140.],
+    );
+    colorbar.draw_bitmap(&mut img, img_shape.0);
+    let mut colorbar_h = Colorbar::new(
+        cmap.reversed(),
+        (0.01, 1000.),
+        Orientation::Horizontal,
+        [80., 20., 180., 35.],
+    );
+    colorbar_h.scale = Scale::Log;
+    colorbar_h.font_size = 14.;
+    colorbar_h.draw_bitmap(&mut img, img_shape.0);
+    {
+        use slice_of_array::SliceFlatExt;
+        let img_data = img
+            .flat()
+            .iter()
+            .map(|&v| v as f32 / 255.)
+            .collect::<Vec<_>>();
+        crate::write_png_from_float_image("target/colorbar.png", img_shape, 3, &img_data)?;
+    }
+    // top of the vertical bar is the end of the colormap
+    let to_u8 = |c: [f32; 3]| c.map(|v| (v * 255.).round() as u8);
+    assert_eq!(img[139 * img_shape.0 + 30], to_u8(cmap.eval(0.5 / 120.)));
+    assert_eq!(
+        img[20 * img_shape.0 + 30],
+        to_u8(cmap.eval(1. - 0.5 / 120.))
+    );
+    // frame and a tick mark
+    assert_eq!(img[19 * img_shape.0 + 30], [0, 0, 0]);
+    assert_eq!(img[80 * img_shape.0 + 42], [0, 0, 0]);
+    assert_eq!(colorbar.tick_labels(&colorbar.tick_values()).len(), 5);
+    //
+    let mut canvas = crate::canvas_svg::Canvas::new("target/colorbar.svg".to_string(), (200, 160));
+    colorbar.draw_svg(&mut canvas);
+    // the arrow marker pushed in between does not make the ids collide
+    let style_arrow = crate::canvas_svg::Style {
+        arrow_end: true,
+        ..Default::default()
+    };
+    canvas.segment(
+        &[0., 0.],
+        &[10., 10.],
+        &[1., 0., 0., 0., 1., 0., 0., 0., 1.],
+        &style_arrow,
+    );
+    colorbar_h.draw_svg(&mut canvas);
+    canvas.write()?;
+    let svg = canvas.to_string();
+    assert!(svg.contains("<linearGradient id=\"def-0\""));
+    assert!(svg.contains("<linearGradient id=\"def-2\""));
+    assert!(svg.contains("fill=\"url(#def-2)\""));
+    assert!(svg.contains(">0.01</text>"));
+    assert!(svg.contains(">-0.5</text>"));
+    Ok(())
+}
@@ -547,6 +547,1098 @@ pub const COLORMAP_JET: [[f32; 3]; 9] = [
     [0.5, 0.0, 0.0],
 ];
 
+/// colors from `0xRRGGBB`
+const fn table_from_hex<const N: usize>(hex: [u32; N]) -> [[f32; 3]; N] {
+    let mut table = [[0f32; 3]; N];
+    let mut i = 0;
+    while i < N {
+        table[i] = [
+            ((hex[i] >> 16) & 0xff) as f32 / 255.,
+            ((hex[i] >> 8) & 0xff) as f32 / 255.,
+            (hex[i] & 0xff) as f32 / 255.,
+        ];
+        i += 1;
+    }
+    table
+}
+
+/// matplotlib's magma
+pub const COLORMAP_MAGMA: [[f32; 3]; 256] = [
+    [0.001462, 0.000466, 0.013866],
+    [0.002258, 0.001295, 0.018331],
+    [0.003279, 0.002305, 0.023708],
+    [0.004512, 0.003490, 0.029965],
+    [0.005950, 0.004843, 0.037130],
+    [0.007588, 0.006356, 0.044973],
+    [0.009426, 0.008022, 0.052844],
+    [0.011465, 0.009828, 0.060750],
+    [0.013708, 0.011771, 0.068667],
+    [0.016156, 0.013840, 0.076603],
+    [0.018815, 0.016026, 0.084584],
+    [0.021692, 0.018320, 0.092610],
+    [0.024792, 0.020715, 0.100676],
+    [0.028123, 0.023201, 0.108787],
+    [0.031696, 0.025765, 0.116965],
+    [0.035520, 0.028397, 0.125209],
+    [0.039608, 0.031090, 0.133515],
+    [0.043830, 0.033830, 0.141886],
+    [0.048062, 0.036607, 0.150327],
+    [0.052320, 0.039407, 0.158841],
+    [0.056615, 0.042160, 0.167446],
+    [0.060949, 0.044794, 0.176129],
+    [0.065330, 0.047318, 0.184892],
+    [0.069764, 0.049726, 0.193735],
+    [0.074257, 0.052017, 0.202660],
+    [0.078815, 0.054184, 0.211667],
+    [0.083446, 0.056225, 0.220755],
+    [0.088155, 0.058133, 0.229922],
+    [0.092949, 0.059904, 0.239164],
+    [0.097833, 0.061531, 0.248477],
+    [0.102815, 0.063010, 0.257854],
+    [0.107899, 0.064335, 0.267289],
+    [0.113094, 0.065492, 0.276784],
+    [0.118405, 0.066479, 0.286321],
+    [0.123833, 0.067295, 0.295879],
+    [0.129380, 0.067935, 0.305443],
+    [0.135053, 0.068391, 0.315000],
+    [0.140858, 0.068654, 0.324538],
+    [0.146785, 0.068738, 0.334011],
+    [0.152839, 0.068637, 0.343404],
+    [0.159018, 0.068354, 0.352688],
+    [0.165308, 0.067911, 0.361816],
+    [0.171713, 0.067305, 0.370771],
+    [0.178212, 0.066576, 0.379497],
+    [0.184801, 0.065732, 0.387973],
+    [0.191460, 0.064818, 0.396152],
+    [0.198177, 0.063862, 0.404009],
+    [0.204935, 0.062907, 0.411514],
+    [0.211718, 0.061992, 0.418647],
+    [0.218512, 0.061158, 0.425392],
+    [0.225302, 0.060445, 0.431742],
+    [0.232077, 0.059889, 0.437695],
+    [0.238826, 0.059517, 0.443256],
+    [0.245543, 0.059352, 0.448436],
+    [0.252220, 0.059415, 0.453248],
+    [0.258857, 0.059706, 0.457710],
+    [0.265447, 0.060237, 0.461840],
+    [0.271994, 0.060994, 0.465660],
+    [0.278493, 0.061978, 0.469190],
+    [0.284951, 0.063168, 0.472451],
+    [0.291366, 0.064553, 0.475462],
+    [0.297740, 0.066117, 0.478243],
+    [0.304081, 0.067835, 0.480812],
+    [0.310382, 0.069702, 0.483186],
+    [0.316654, 0.071690, 0.485380],
+    [0.322899, 0.073782, 0.487408],
+    [0.329114, 0.075972, 0.489287],
+    [0.335308, 0.078236, 0.491024],
+    [0.341482, 0.080564, 0.492631],
+    [0.347636, 0.082946, 0.494121],
+    [0.353773, 0.085373, 0.495501],
+    [0.359898, 0.087831, 0.496778],
+    [0.366012, 0.090314, 0.497960],
+    [0.372116, 0.092816, 0.499053],
+    [0.378211, 0.095332, 0.500067],
+    [0.384299, 0.097855, 0.501002],
+    [0.390384, 0.100379, 0.501864],
+    [0.396467, 0.102902, 0.502658],
+    [0.402548, 0.105420, 0.503386],
+    [0.408629, 0.107930, 0.504052],
+    [0.414709, 0.110431, 0.504662],
+    [0.420791, 0.112920, 0.505215],
+    [0.426877, 0.115395, 0.505714],
+    [0.432967, 0.117855, 0.506160],
+    [0.439062, 0.120298, 0.506555],
+    [0.445163, 0.122724, 0.506901],
+    [0.451271, 0.125132, 0.507198],
+    [0.457386, 0.127522, 0.507448],
+    [0.463508, 0.129893, 0.507652],
+    [0.469640, 0.132245, 0.507809],
+    [0.475780, 0.134577, 0.507921],
+    [0.481929, 0.136891, 0.507989],
+    [0.488088, 0.139186, 0.508011],
+    [0.494258, 0.141462, 0.507988],
+    [0.500438, 0.143719, 0.507920],
+    [0.506629, 0.145958, 0.507806],
+    [0.512831, 0.148179, 0.507648],
+    [0.519045, 0.150383, 0.507443],
+    [0.525270, 0.152569, 0.507192],
+    [0.531507, 0.154739, 0.506895],
+    [0.537755, 0.156894, 0.506551],
+    [0.544015, 0.159033, 0.506159],
+    [0.550287, 0.161158, 0.505719],
+    [0.556571, 0.163269, 0.505230],
+    [0.562866, 0.165368, 0.504692],
+    [0.569172, 0.167454, 0.504105],
+    [0.575490, 0.169530, 0.503466],
+    [0.581819, 0.171596, 0.502777],
+    [0.588158, 0.173652, 0.502035],
+    [0.594508, 0.175701, 0.501241],
+    [0.600868, 0.177743, 0.500394],
+    [0.607238, 0.179779, 0.499492],
+    [0.613617, 0.181811, 0.498536],
+    [0.620005, 0.183840, 0.497524],
+    [0.626401, 0.185867, 0.496456],
+    [0.632805, 0.187893, 0.495332],
+    [0.639216, 0.189921, 0.494150],
+    [0.645633, 0.191952, 0.492910],
+    [0.652056, 0.193986, 0.491611],
+    [0.658483, 0.196027, 0.490253],
+    [0.664915, 0.198075, 0.488836],
+    [0.671349, 0.200133, 0.487358],
+    [0.677786, 0.202203, 0.485819],
+    [0.684224, 0.204286, 0.484219],
+    [0.690661, 0.206384, 0.482558],
+    [0.697098, 0.208501, 0.480835],
+    [0.703532, 0.210638, 0.479049],
+    [0.709962, 0.212797, 0.477201],
+    [0.716387, 0.214982, 0.475290],
+    [0.722805, 0.217194, 0.473316],
+    [0.729216, 0.219437, 0.471279],
+    [0.735616, 0.221713, 0.469180],
+    [0.742004, 0.224025, 0.467018],
+    [0.748378, 0.226377, 0.464794],
+    [0.754737, 0.228772, 0.462509],
+    [0.761077, 0.231214, 0.460162],
+    [0.767398, 0.233705, 0.457755],
+    [0.773695, 0.236249, 0.455289],
+    [0.779968, 0.238851, 0.452765],
+    [0.786212, 0.241514, 0.450184],
+    [0.792427, 0.244242, 0.447543],
+    [0.798608, 0.247040, 0.444848],
+    [0.804752, 0.249911, 0.442102],
+    [0.810855, 0.252861, 0.439305],
+    [0.816914, 0.255895, 0.436461],
+    [0.822926, 0.259016, 0.433573],
+    [0.828886, 0.262229, 0.430644],
+    [0.834791, 0.265540, 0.427671],
+    [0.840636, 0.268953, 0.424666],
+    [0.846416, 0.272473, 0.421631],
+    [0.852126, 0.276106, 0.418573],
+    [0.857763, 0.279857, 0.415496],
+    [0.863320, 0.283729, 0.412403],
+    [0.868793, 0.287728, 0.409303],
+    [0.874176, 0.291859, 0.406205],
+    [0.879464, 0.296125, 0.403118],
+    [0.884651, 0.300530, 0.400047],
+    [0.889731, 0.305079, 0.397002],
+    [0.894700, 0.309773, 0.393995],
+    [0.899552, 0.314616, 0.391037],
+    [0.904281, 0.319610, 0.388137],
+    [0.908884, 0.324755, 0.385308],
+    [0.913354, 0.330052, 0.382563],
+    [0.917689, 0.335500, 0.379915],
+    [0.921884, 0.341098, 0.377376],
+    [0.925937, 0.346844, 0.374959],
+    [0.929845, 0.352734, 0.372677],
+    [0.933606, 0.358764, 0.370541],
+    [0.937221, 0.364929, 0.368567],
+    [0.940687, 0.371224, 0.366762],
+    [0.944006, 0.377643, 0.365136],
+    [0.947180, 0.384178, 0.363701],
+    [0.950210, 0.390820, 0.362468],
+    [0.953099, 0.397563, 0.361438],
+    [0.955849, 0.404400, 0.360619],
+    [0.958464, 0.411324, 0.360014],
+    [0.960949, 0.418323, 0.359630],
+    [0.963310, 0.425390, 0.359469],
+    [0.965549, 0.432519, 0.359529],
+    [0.967671, 0.439703, 0.359810],
+    [0.969680, 0.446936, 0.360311],
+    [0.971582, 0.454210, 0.361030],
+    [0.973381, 0.461520, 0.361965],
+    [0.975082, 0.468861, 0.363111],
+    [0.976690, 0.476226, 0.364466],
+    [0.978210, 0.483612, 0.366025],
+    [0.979645, 0.491014, 0.367783],
+    [0.981000, 0.498428, 0.369734],
+    [0.982279, 0.505851, 0.371874],
+    [0.983485, 0.513280, 0.374198],
+    [0.984622, 0.520713, 0.376698],
+    [0.985693, 0.528148, 0.379371],
+    [0.986700, 0.535582, 0.382210],
+    [0.987646, 0.543015, 0.385210],
+    [0.988533, 0.550446, 0.388365],
+    [0.989363, 0.557873, 0.391671],
+    [0.990138, 0.565296, 0.395122],
+    [0.990871, 0.572706, 0.398714],
+    [0.991558, 0.580107, 0.402441],
+    [0.992196, 0.587502, 0.406299],
+    [0.992785, 0.594891, 0.410283],
+    [0.993326, 0.602275, 0.414390],
+    [0.993834, 0.609644, 0.418613],
+    [0.994309, 0.616999, 0.422950],
+    [0.994738, 0.624350, 0.427397],
+    [0.995122, 0.631696, 0.431951],
+    [0.995480, 0.639027, 0.436607],
+    [0.995810, 0.646344, 0.441361],
+    [0.996096, 0.653659, 0.446213],
+    [0.996341, 0.660969, 0.451160],
+    [0.996580, 0.668256, 0.456192],
+    [0.996775, 0.675541, 0.461314],
+    [0.996925, 0.682828, 0.466526],
+    [0.997077, 0.690088, 0.471811],
+    [0.997186, 0.697349, 0.477182],
+    [0.997254, 0.704611, 0.482635],
+    [0.997325, 0.711848, 0.488154],
+    [0.997351, 0.719089, 0.493755],
+    [0.997351, 0.726324, 0.499428],
+    [0.997341, 0.733545, 0.505167],
+    [0.997285, 0.740772, 0.510983],
+    [0.997228, 0.747981, 0.516859],
+    [0.997138, 0.755190, 0.522806],
+    [0.997019, 0.762398, 0.528821],
+    [0.996898, 0.769591, 0.534892],
+    [0.996727, 0.776795, 0.541039],
+    [0.996571, 0.783977, 0.547233],
+    [0.996369, 0.791167, 0.553499],
+    [0.996162, 0.798348, 0.559820],
+    [0.995932, 0.805527, 0.566202],
+    [0.995680, 0.812706, 0.572645],
+    [0.995424, 0.819875, 0.579140],
+    [0.995131, 0.827052, 0.585701],
+    [0.994851, 0.834213, 0.592307],
+    [0.994524, 0.841387, 0.598983],
+    [0.994222, 0.848540, 0.605696],
+    [0.993866, 0.855711, 0.612482],
+    [0.993545, 0.862859, 0.619299],
+    [0.993170, 0.870024, 0.626189],
+    [0.992831, 0.877168, 0.633109],
+    [0.992440, 0.884330, 0.640099],
+    [0.992089, 0.891470, 0.647116],
+    [0.991688, 0.898627, 0.654202],
+    [0.991332, 0.905763, 0.661309],
+    [0.990930, 0.912915, 0.668481],
+    [0.990570, 0.920049, 0.675675],
+    [0.990175, 0.927196, 0.682926],
+    [0.989815, 0.934329, 0.690198],
+    [0.989434, 0.941470, 0.697519],
+    [0.989077, 0.948604, 0.704863],
+    [0.988717, 0.955742, 0.712242],
+    [0.988367, 0.962878, 0.719649],
+    [0.988033, 0.970012, 0.727077],
+    [0.987691, 0.977154, 0.734536],
+    [0.987387, 0.984288, 0.742002],
+    [0.987053, 0.991438, 0.749504],
+];
+
+/// matplotlib's inferno
+pub const COLORMAP_INFERNO: [[f32; 3]; 256] = [
+    [0.001462, 0.000466, 0.013866],
+    [0.002267, 0.001270, 0.018570],
+    [0.003299, 0.002249, 0.024239],
+    [0.004547, 0.003392, 0.030909],
+    [0.006006, 0.004692, 0.038558],
+    [0.007676, 0.006136, 0.046836],
+    [0.009561, 0.007713, 0.055143],
+    [0.011663, 0.009417, 0.063460],
+    [0.013995, 0.011225, 0.071862],
+    [0.016561, 0.013136, 0.080282],
+    [0.019373, 0.015133, 0.088767],
+    [0.022447, 0.017199, 0.097327],
+    [0.025793, 0.019331, 0.105930],
+    [0.029432, 0.021503, 0.114621],
+    [0.033385, 0.023702, 0.123397],
+    [0.037668, 0.025921, 0.132232],
+    [0.042253, 0.028139, 0.141141],
+    [0.046915, 0.030324, 0.150164],
+    [0.051644, 0.032474, 0.159254],
+    [0.056449, 0.034569, 0.168414],
+    [0.061340, 0.036590, 0.177642],
+    [0.066331, 0.038504, 0.186962],
+    [0.071429, 0.040294, 0.196354],
+    [0.076637, 0.041905, 0.205799],
+    [0.081962, 0.043328, 0.215289],
+    [0.087411, 0.044556, 0.224813],
+    [0.092990, 0.045583, 0.234358],
+    [0.098702, 0.046402, 0.243904],
+    [0.104551, 0.047008, 0.253430],
+    [0.110536, 0.047399, 0.262912],
+    [0.116656, 0.047574, 0.272321],
+    [0.122908, 0.047536, 0.281624],
+    [0.129285, 0.047293, 0.290788],
+    [0.135778, 0.046856, 0.299776],
+    [0.142378, 0.046242, 0.308553],
+    [0.149073, 0.045468, 0.317085],
+    [0.155850, 0.044559, 0.325338],
+    [0.162689, 0.043554, 0.333277],
+    [0.169575, 0.042489, 0.340874],
+    [0.176493, 0.041402, 0.348111],
+    [0.183429, 0.040329, 0.354971],
+    [0.190367, 0.039309, 0.361447],
+    [0.197297, 0.038400, 0.367535],
+    [0.204209, 0.037632, 0.373238],
+    [0.211095, 0.037030, 0.378563],
+    [0.217949, 0.036615, 0.383522],
+    [0.224763, 0.036405, 0.388129],
+    [0.231538, 0.036405, 0.392400],
+    [0.238273, 0.036621, 0.396353],
+    [0.244967, 0.037055, 0.400007],
+    [0.251620, 0.037705, 0.403378],
+    [0.258234, 0.038571, 0.406485],
+    [0.264810, 0.039647, 0.409345],
+    [0.271347, 0.040922, 0.411976],
+    [0.277850, 0.042353, 0.414392],
+    [0.284321, 0.043933, 0.416608],
+    [0.290763, 0.045644, 0.418637],
+    [0.297178, 0.047470, 0.420491],
+    [0.303568, 0.049396, 0.422182],
+    [0.309935, 0.051407, 0.423721],
+    [0.316282, 0.053490, 0.425116],
+    [0.322610, 0.055634, 0.426377],
+    [0.328921, 0.057827, 0.427511],
+    [0.335217, 0.060060, 0.428524],
+    [0.341500, 0.062325, 0.429425],
+    [0.347771, 0.064616, 0.430217],
+    [0.354032, 0.066925, 0.430906],
+    [0.360284, 0.069247, 0.431497],
+    [0.366529, 0.071579, 0.431994],
+    [0.372768, 0.073915, 0.432400],
+    [0.379001, 0.076253, 0.432719],
+    [0.385228, 0.078591, 0.432955],
+    [0.391453, 0.080927, 0.433109],
+    [0.397674, 0.083257, 0.433183],
+    [0.403894, 0.085580, 0.433179],
+    [0.410113, 0.087896, 0.433098],
+    [0.416331, 0.090203, 0.432943],
+    [0.422549, 0.092501, 0.432714],
+    [0.428768, 0.094790, 0.432412],
+    [0.434987, 0.097069, 0.432039],
+    [0.441207, 0.099338, 0.431594],
+    [0.447428, 0.101597, 0.431080],
+    [0.453651, 0.103848, 0.430498],
+    [0.459875, 0.106089, 0.429846],
+    [0.466100, 0.108322, 0.429125],
+    [0.472328, 0.110547, 0.428334],
+    [0.478558, 0.112764, 0.427475],
+    [0.484789, 0.114974, 0.426548],
+    [0.491022, 0.117179, 0.425552],
+    [0.497257, 0.119379, 0.424488],
+    [0.503493, 0.121575, 0.423356],
+    [0.509730, 0.123769, 0.422156],
+    [0.515967, 0.125960, 0.420887],
+    [0.522206, 0.128150, 0.419549],
+    [0.528444, 0.130341, 0.418142],
+    [0.534683, 0.132534, 0.416667],
+    [0.540920, 0.134729, 0.415123],
+    [0.547157, 0.136929, 0.413511],
+    [0.553392, 0.139134, 0.411829],
+    [0.559624, 0.141346, 0.410078],
+    [0.565854, 0.143567, 0.408258],
+    [0.572081, 0.145797, 0.406369],
+    [0.578304, 0.148039, 0.404411],
+    [0.584521, 0.150294, 0.402385],
+    [0.590734, 0.152563, 0.400290],
+    [0.596940, 0.154848, 0.398125],
+    [0.603139, 0.157151, 0.395891],
+    [0.609330, 0.159474, 0.393589],
+    [0.615513, 0.161817, 0.391219],
+    [0.621685, 0.164184, 0.388781],
+    [0.627847, 0.166575, 0.386276],
+    [0.633998, 0.168992, 0.383704],
+    [0.640135, 0.171438, 0.381065],
+    [0.646260, 0.173914, 0.378359],
+    [0.652369, 0.176421, 0.375586],
+    [0.658463, 0.178962, 0.372748],
+    [0.664540, 0.181539, 0.369846],
+    [0.670599, 0.184153, 0.366879],
+    [0.676638, 0.186807, 0.363849],
+    [0.682656, 0.189501, 0.360757],
+    [0.688653, 0.192239, 0.357603],
+    [0.694627, 0.195021, 0.354388],
+    [0.700576, 0.197851, 0.351113],
+    [0.706500, 0.200728, 0.347777],
+    [0.712396, 0.203656, 0.344383],
+    [0.718264, 0.206636, 0.340931],
+    [0.724103, 0.209670, 0.337424],
+    [0.729909, 0.212759, 0.333861],
+    [0.735683, 0.215906, 0.330245],
+    [0.741423, 0.219112, 0.326576],
+    [0.747127, 0.222378, 0.322856],
+    [0.752794, 0.225706, 0.319085],
+    [0.758422, 0.229097, 0.315266],
+    [0.764010, 0.232554, 0.311399],
+    [0.769556, 0.236077, 0.307485],
+    [0.775059, 0.239667, 0.303526],
+    [0.780517, 0.243327, 0.299523],
+    [0.785929, 0.247056, 0.295477],
+    [0.791293, 0.250856, 0.291390],
+    [0.796607, 0.254728, 0.287264],
+    [0.801871, 0.258674, 0.283099],
+    [0.807082, 0.262692, 0.278898],
+    [0.812239, 0.266786, 0.274661],
+    [0.817341, 0.270954, 0.270390],
+    [0.822386, 0.275197, 0.266085],
+    [0.827372, 0.279517, 0.261750],
+    [0.832299, 0.283913, 0.257383],
+    [0.837165, 0.288385, 0.252988],
+    [0.841969, 0.292933, 0.248564],
+    [0.846709, 0.297559, 0.244113],
+    [0.851384, 0.302260, 0.239636],
+    [0.855992, 0.307038, 0.235133],
+    [0.860533, 0.311892, 0.230606],
+    [0.865006, 0.316822, 0.226055],
+    [0.869409, 0.321827, 0.221482],
+    [0.873741, 0.326906, 0.216886],
+    [0.878001, 0.332060, 0.212268],
+    [0.882188, 0.337287, 0.207628],
+    [0.886302, 0.342586, 0.202968],
+    [0.890341, 0.347957, 0.198286],
+    [0.894305, 0.353399, 0.193584],
+    [0.898192, 0.358911, 0.188860],
+    [0.902003, 0.364492, 0.184116],
+    [0.905735, 0.370140, 0.179350],
+    [0.909390, 0.375856, 0.174563],
+    [0.912966, 0.381636, 0.169755],
+    [0.916462, 0.387481, 0.164924],
+    [0.919879, 0.393389, 0.160070],
+    [0.923215, 0.399359, 0.155193],
+    [0.926470, 0.405389, 0.150292],
+    [0.929644, 0.411479, 0.145367],
+    [0.932737, 0.417627, 0.140417],
+    [0.935747, 0.423831, 0.135440],
+    [0.938675, 0.430091, 0.130438],
+    [0.941521, 0.436405, 0.125409],
+    [0.944285, 0.442772, 0.120354],
+    [0.946965, 0.449191, 0.115272],
+    [0.949562, 0.455660, 0.110164],
+    [0.952075, 0.462178, 0.105031],
+    [0.954506, 0.468744, 0.099874],
+    [0.956852, 0.475356, 0.094695],
+    [0.959114, 0.482014, 0.089499],
+    [0.961293, 0.488716, 0.084289],
+    [0.963387, 0.495462, 0.079073],
+    [0.965397, 0.502249, 0.073859],
+    [0.967322, 0.509078, 0.068659],
+    [0.969163, 0.515946, 0.063488],
+    [0.970919, 0.522853, 0.058367],
+    [0.972590, 0.529798, 0.053324],
+    [0.974176, 0.536780, 0.048392],
+    [0.975677, 0.543798, 0.043618],
+    [0.977092, 0.550850, 0.039050],
+    [0.978422, 0.557937, 0.034931],
+    [0.979666, 0.565057, 0.031409],
+    [0.980824, 0.572209, 0.028508],
+    [0.981895, 0.579392, 0.026250],
+    [0.982881, 0.586606, 0.024661],
+    [0.983779, 0.593849, 0.023770],
+    [0.984591, 0.601122, 0.023606],
+    [0.985315, 0.608422, 0.024202],
+    [0.985952, 0.615750, 0.025592],
+    [0.986502, 0.623105, 0.027814],
+    [0.986964, 0.630485, 0.030908],
+    [0.987337, 0.637890, 0.034916],
+    [0.987622, 0.645320, 0.039886],
+    [0.987819, 0.652773, 0.045581],
+    [0.987926, 0.660250, 0.051750],
+    [0.987945, 0.667748, 0.058329],
+    [0.987874, 0.675267, 0.065257],
+    [0.987714, 0.682807, 0.072489],
+    [0.987464, 0.690366, 0.079990],
+    [0.987124, 0.697944, 0.087731],
+    [0.986694, 0.705540, 0.095694],
+    [0.986175, 0.713153, 0.103863],
+    [0.985566, 0.720782, 0.112229],
+    [0.984865, 0.728427, 0.120785],
+    [0.984075, 0.736087, 0.129527],
+    [0.983196, 0.743758, 0.138453],
+    [0.982228, 0.751442, 0.147565],
+    [0.981173, 0.759135, 0.156863],
+    [0.980032, 0.766837, 0.166353],
+    [0.978806, 0.774545, 0.176037],
+    [0.977497, 0.782258, 0.185923],
+    [0.976108, 0.789974, 0.196018],
+    [0.974638, 0.797692, 0.206332],
+    [0.973088, 0.805409, 0.216877],
+    [0.971468, 0.813122, 0.227658],
+    [0.969783, 0.820825, 0.238686],
+    [0.968041, 0.828515, 0.249972],
+    [0.966243, 0.836191, 0.261534],
+    [0.964394, 0.843848, 0.273391],
+    [0.962517, 0.851476, 0.285546],
+    [0.960626, 0.859069, 0.298010],
+    [0.958720, 0.866624, 0.310820],
+    [0.956834, 0.874129, 0.323974],
+    [0.954997, 0.881569, 0.337475],
+    [0.953215, 0.888942, 0.351369],
+    [0.951546, 0.896226, 0.365627],
+    [0.950018, 0.903409, 0.380271],
+    [0.948683, 0.910473, 0.395289],
+    [0.947594, 0.917399, 0.410665],
+    [0.946809, 0.924168, 0.426373],
+    [0.946392, 0.930761, 0.442367],
+    [0.946403, 0.937159, 0.458592],
+    [0.946903, 0.943348, 0.474970],
+    [0.947937, 0.949318, 0.491426],
+    [0.949545, 0.955063, 0.507860],
+    [0.951740, 0.960587, 0.524203],
+    [0.954529, 0.965896, 0.540361],
+    [0.957896, 0.971003, 0.556275],
+    [0.961812, 0.975924, 0.571925],
+    [0.966249, 0.980678, 0.587206],
+    [0.971162, 0.985282, 0.602154],
+    [0.976511, 0.989753, 0.616760],
+    [0.982257, 0.994109, 0.631017],
+    [0.988362, 0.998364, 0.644924],
+];
+
+/// Google's turbo, the improved rainbow colormap
+pub const COLORMAP_TURBO: [[f32; 3]; 256] = [
+    [0.18995, 0.07176, 0.23217],
+    [0.19483, 0.08339, 0.26149],
+    [0.19956, 0.09498, 0.29024],
+    [0.20415, 0.10652, 0.31844],
+    [0.20860, 0.11802, 0.34607],
+    [0.21291, 0.12947, 0.37314],
+    [0.21708, 0.14087, 0.39964],
+    [0.22111, 0.15223, 0.42558],
+    [0.22500, 0.16354, 0.45096],
+    [0.22875, 0.17481, 0.47578],
+    [0.23236, 0.18603, 0.50004],
+    [0.23582, 0.19720, 0.52373],
+    [0.23915, 0.20833, 0.54686],
+    [0.24234, 0.21941, 0.56942],
+    [0.24539, 0.23044, 0.59142],
+    [0.24830, 0.24143, 0.61286],
+    [0.25107, 0.25237, 0.63374],
+    [0.25369, 0.26327, 0.65406],
+    [0.25618, 0.27412, 0.67381],
+    [0.25853, 0.28492, 0.69300],
+    [0.26074, 0.29568, 0.71162],
+    [0.26280, 0.30639, 0.72968],
+    [0.26473, 0.31706, 0.74718],
+    [0.26652, 0.32768, 0.76412],
+    [0.26816, 0.33825, 0.78050],
+    [0.26967, 0.34878, 0.79631],
+    [0.27103, 0.35926, 0.81156],
+    [0.27226, 0.36970, 0.82624],
+    [0.27334, 0.38008, 0.84037],
+    [0.27429, 0.39043, 0.85393],
+    [0.27509, 0.40072, 0.86692],
+    [0.27576, 0.41097, 0.87936],
+    [0.27628, 0.42118, 0.89123],
+    [0.27667, 0.43134, 0.90254],
+    [0.27691, 0.44145, 0.91328],
+    [0.27701, 0.45152, 0.92347],
+    [0.27698, 0.46153, 0.93309],
+    [0.27680, 0.47151, 0.94214],
+    [0.27648, 0.48144, 0.95064],
+    [0.27603, 0.49132, 0.95857],
+    [0.27543, 0.50115, 0.96594],
+    [0.27469, 0.51094, 0.97275],
+    [0.27381, 0.52069, 0.97899],
+    [0.27273, 0.53040, 0.98461],
+    [0.27106, 0.54015, 0.98930],
+    [0.26878, 0.54995, 0.99303],
+    [0.26592, 0.55979, 0.99583],
+    [0.26252, 0.56967, 0.99773],
+    [0.25862, 0.57958, 0.99876],
+    [0.25425, 0.58950, 0.99896],
+    [0.24946, 0.59943, 0.99835],
+    [0.24427, 0.60937, 0.99697],
+    [0.23874, 0.61931, 0.99485],
+    [0.23288, 0.62923, 0.99202],
+    [0.22676, 0.63913, 0.98851],
+    [0.22039, 0.64901, 0.98436],
+    [0.21382, 0.65886, 0.97959],
+    [0.20708, 0.66866, 0.97423],
+    [0.20021, 0.67842, 0.96833],
+    [0.19326, 0.68812, 0.96190],
+    [0.18625, 0.69775, 0.95498],
+    [0.17923, 0.70732, 0.94761],
+    [0.17223, 0.71680, 0.93981],
+    [0.16529, 0.72620, 0.93161],
+    [0.15844, 0.73551, 0.92305],
+    [0.15173, 0.74472, 0.91416],
+    [0.14519, 0.75381, 0.90496],
+    [0.13886, 0.76279, 0.89550],
+    [0.13278, 0.77165, 0.88580],
+    [0.12698, 0.78037, 0.87590],
+    [0.12151, 0.78896, 0.86581],
+    [0.11639, 0.79740, 0.85559],
+    [0.11167, 0.80569, 0.84525],
+    [0.10738, 0.81381, 0.83484],
+    [0.10357, 0.82177, 0.82437],
+    [0.10026, 0.82955, 0.81389],
+    [0.09750, 0.83714, 0.80342],
+    [0.09532, 0.84455, 0.79299],
+    [0.09377, 0.85175, 0.78264],
+    [0.09287, 0.85875, 0.77240],
+    [0.09267, 0.86554, 0.76230],
+    [0.09320, 0.87211, 0.75237],
+    [0.09451, 0.87844, 0.74265],
+    [0.09662, 0.88454, 0.73316],
+    [0.09958, 0.89040, 0.72393],
+    [0.10342, 0.89600, 0.71500],
+    [0.10815, 0.90142, 0.70599],
+    [0.11374, 0.90673, 0.69651],
+    [0.12014, 0.91193, 0.68660],
+    [0.12733, 0.91701, 0.67627],
+    [0.13526, 0.92197, 0.66556],
+    [0.14391, 0.92680, 0.65448],
+    [0.15323, 0.93151, 0.64308],
+    [0.16319, 0.93609, 0.63137],
+    [0.17377, 0.94053, 0.61938],
+    [0.18491, 0.94484, 0.60713],
+    [0.19659, 0.94901, 0.59466],
+    [0.20877, 0.95304, 0.58199],
+    [0.22142, 0.95692, 0.56914],
+    [0.23449, 0.96065, 0.55614],
+    [0.24797, 0.96423, 0.54303],
+    [0.26180, 0.96765, 0.52981],
+    [0.27597, 0.97092, 0.51653],
+    [0.29042, 0.97403, 0.50321],
+    [0.30513, 0.97697, 0.48987],
+    [0.32006, 0.97974, 0.47654],
+    [0.33517, 0.98234, 0.46325],
+    [0.35043, 0.98477, 0.45002],
+    [0.36581, 0.98702, 0.43688],
+    [0.38127, 0.98909, 0.42386],
+    [0.39678, 0.99098, 0.41098],
+    [0.41229, 0.99268, 0.39826],
+    [0.42778, 0.99419, 0.38575],
+    [0.44321, 0.99551, 0.37345],
+    [0.45854, 0.99663, 0.36140],
+    [0.47375, 0.99755, 0.34963],
+    [0.48879, 0.99828, 0.33816],
+    [0.50362, 0.99879, 0.32701],
+    [0.51822, 0.99910, 0.31622],
+    [0.53255, 0.99919, 0.30581],
+    [0.54658, 0.99907, 0.29581],
+    [0.56026, 0.99873, 0.28623],
+    [0.57357, 0.99817, 0.27712],
+    [0.58646, 0.99739, 0.26849],
+    [0.59891, 0.99638, 0.26038],
+    [0.61088, 0.99514, 0.25280],
+    [0.62233, 0.99366, 0.24579],
+    [0.63323, 0.99195, 0.23937],
+    [0.64362, 0.98999, 0.23356],
+    [0.65394, 0.98775, 0.22835],
+    [0.66428, 0.98524, 0.22370],
+    [0.67462, 0.98246, 0.21960],
+    [0.68494, 0.97941, 0.21602],
+    [0.69525, 0.97610, 0.21294],
+    [0.70553, 0.97255, 0.21032],
+    [0.71577, 0.96875, 0.20815],
+    [0.72596, 0.96470, 0.20640],
+    [0.73610, 0.96043, 0.20504],
+    [0.74617, 0.95593, 0.20406],
+    [0.75617, 0.95121, 0.20343],
+    [0.76608, 0.94627, 0.20311],
+    [0.77591, 0.94113, 0.20310],
+    [0.78563, 0.93579, 0.20336],
+    [0.79524, 0.93025, 0.20386],
+    [0.80473, 0.92452, 0.20459],
+    [0.81410, 0.91861, 0.20552],
+    [0.82333, 0.91253, 0.20663],
+    [0.83241, 0.90627, 0.20788],
+    [0.84133, 0.89986, 0.20926],
+    [0.85010, 0.89328, 0.21074],
+    [0.85868, 0.88655, 0.21230],
+    [0.86709, 0.87968, 0.21391],
+    [0.87530, 0.87267, 0.21555],
+    [0.88331, 0.86553, 0.21719],
+    [0.89112, 0.85826, 0.21880],
+    [0.89870, 0.85087, 0.22038],
+    [0.90605, 0.84337, 0.22188],
+    [0.91317, 0.83576, 0.22328],
+    [0.92004, 0.82806, 0.22456],
+    [0.92666, 0.82025, 0.22570],
+    [0.93301, 0.81236, 0.22667],
+    [0.93909, 0.80439, 0.22744],
+    [0.94489, 0.79634, 0.22800],
+    [0.95039, 0.78823, 0.22831],
+    [0.95560, 0.78005, 0.22836],
+    [0.96049, 0.77181, 0.22811],
+    [0.96507, 0.76352, 0.22754],
+    [0.96931, 0.75519, 0.22663],
+    [0.97323, 0.74682, 0.22536],
+    [0.97679, 0.73842, 0.22369],
+    [0.98000, 0.73000, 0.22161],
+    [0.98289, 0.72140, 0.21918],
+    [0.98549, 0.71250, 0.21650],
+    [0.98781, 0.70330, 0.21358],
+    [0.98986, 0.69382, 0.21043],
+    [0.99163, 0.68408, 0.20706],
+    [0.99314, 0.67408, 0.20348],
+    [0.99438, 0.66386, 0.19971],
+    [0.99535, 0.65341, 0.19577],
+    [0.99607, 0.64277, 0.19165],
+    [0.99654, 0.63193, 0.18738],
+    [0.99675, 0.62093, 0.18297],
+    [0.99672, 0.60977, 0.17842],
+    [0.99644, 0.59846, 0.17376],
+    [0.99593, 0.58703, 0.16899],
+    [0.99517, 0.57549, 0.16412],
+    [0.99419, 0.56386, 0.15918],
+    [0.99297, 0.55214, 0.15417],
+    [0.99153, 0.54036, 0.14910],
+    [0.98987, 0.52854, 0.14398],
+    [0.98799, 0.51667, 0.13883],
+    [0.98590, 0.50479, 0.13367],
+    [0.98360, 0.49291, 0.12849],
+    [0.98108, 0.48104, 0.12332],
+    [0.97837, 0.46920, 0.11817],
+    [0.97545, 0.45740, 0.11305],
+    [0.97234, 0.44565, 0.10797],
+    [0.96904, 0.43399, 0.10294],
+    [0.96555, 0.42241, 0.09798],
+    [0.96187, 0.41093, 0.09310],
+    [0.95801, 0.39958, 0.08831],
+    [0.95398, 0.38836, 0.08362],
+    [0.94977, 0.37729, 0.07905],
+    [0.94538, 0.36638, 0.07461],
+    [0.94084, 0.35566, 0.07031],
+    [0.93612, 0.34513, 0.06616],
+    [0.93125, 0.33482, 0.06218],
+    [0.92623, 0.32473, 0.05837],
+    [0.92105, 0.31489, 0.05475],
+    [0.91572, 0.30530, 0.05134],
+    [0.91024, 0.29599, 0.04814],
+    [0.90463, 0.28696, 0.04516],
+    [0.89888, 0.27824, 0.04243],
+    [0.89298, 0.26981, 0.03993],
+    [0.88691, 0.26152, 0.03753],
+    [0.88066, 0.25334, 0.03521],
+    [0.87422, 0.24526, 0.03297],
+    [0.86760, 0.23730, 0.03082],
+    [0.86079, 0.22945, 0.02875],
+    [0.85380, 0.22170, 0.02677],
+    [0.84662, 0.21407, 0.02487],
+    [0.83926, 0.20654, 0.02305],
+    [0.83172, 0.19912, 0.02131],
+    [0.82399, 0.19182, 0.01966],
+    [0.81608, 0.18462, 0.01809],
+    [0.80799, 0.17753, 0.01660],
+    [0.79971, 0.17055, 0.01520],
+    [0.79125, 0.16368, 0.01387],
+    [0.78260, 0.15693, 0.01264],
+    [0.77377, 0.15028, 0.01148],
+    [0.76476, 0.14374, 0.01041],
+    [0.75556, 0.13731, 0.00942],
+    [0.74617, 0.13098, 0.00851],
+    [0.73661, 0.12477, 0.00769],
+    [0.72686, 0.11867, 0.00695],
+    [0.71692, 0.11268, 0.00629],
+    [0.70680, 0.10680, 0.00571],
+    [0.69650, 0.10102, 0.00522],
+    [0.68602, 0.09536, 0.00481],
+    [0.67535, 0.08980, 0.00449],
+    [0.66449, 0.08436, 0.00424],
+    [0.65345, 0.07902, 0.00408],
+    [0.64223, 0.07380, 0.00401],
+    [0.63082, 0.06868, 0.00401],
+    [0.61923, 0.06367, 0.00410],
+    [0.60746, 0.05878, 0.00427],
+    [0.59550, 0.05399, 0.00453],
+    [0.58336, 0.04931, 0.00486],
+    [0.57103, 0.04474, 0.00529],
+    [0.55852, 0.04028, 0.00579],
+    [0.54583, 0.03593, 0.00638],
+    [0.53295, 0.03169, 0.00705],
+    [0.51989, 0.02756, 0.00780],
+    [0.50664, 0.02354, 0.00863],
+    [0.49321, 0.01963, 0.00955],
+    [0.47960, 0.01583, 0.01055],
+];
+
+/// matplotlib's cividis (the color-vision-deficiency friendly map)
+pub const COLORMAP_CIVIDIS: [[f32; 3]; 256] = [
+    [0.000000, 0.135112, 0.304751],
+    [0.000000, 0.138068, 0.311105],
+    [0.000000, 0.141013, 0.317579],
+    [0.000000, 0.143951, 0.323982],
+    [0.000000, 0.146877, 0.330479],
+    [0.000000, 0.149791, 0.337065],
+    [0.000000, 0.152673, 0.343704],
+    [0.000000, 0.155377, 0.350500],
+    [0.000000, 0.157932, 0.357521],
+    [0.000000, 0.160495, 0.364534],
+    [0.000000, 0.163058, 0.371608],
+    [0.000000, 0.165621, 0.378769],
+    [0.000000, 0.168204, 0.385902],
+    [0.000000, 0.170800, 0.393100],
+    [0.000000, 0.173420, 0.400353],
+    [0.000000, 0.176082, 0.407577],
+    [0.000000, 0.178802, 0.414764],
+    [0.000000, 0.181610, 0.421859],
+    [0.000000, 0.184550, 0.428802],
+    [0.000000, 0.186915, 0.435532],
+    [0.000000, 0.188769, 0.439563],
+    [0.000000, 0.190950, 0.441085],
+    [0.000000, 0.193366, 0.441561],
+    [0.003602, 0.195911, 0.441564],
+    [0.017852, 0.198528, 0.441248],
+    [0.032110, 0.201199, 0.440785],
+    [0.046205, 0.203903, 0.440196],
+    [0.058378, 0.206629, 0.439531],
+    [0.068968, 0.209372, 0.438863],
+    [0.078624, 0.212122, 0.438105],
+    [0.087465, 0.214879, 0.437342],
+    [0.095645, 0.217643, 0.436593],
+    [0.103401, 0.220406, 0.435790],
+    [0.110658, 0.223170, 0.435067],
+    [0.117612, 0.225935, 0.434308],
+    [0.124291, 0.228697, 0.433547],
+    [0.130669, 0.231458, 0.432840],
+    [0.136830, 0.234216, 0.432148],
+    [0.142852, 0.236972, 0.431404],
+    [0.148638, 0.239724, 0.430752],
+    [0.154261, 0.242475, 0.430120],
+    [0.159733, 0.245221, 0.429528],
+    [0.165113, 0.247965, 0.428908],
+    [0.170362, 0.250707, 0.428325],
+    [0.175490, 0.253444, 0.427790],
+    [0.180503, 0.256180, 0.427299],
+    [0.185453, 0.258914, 0.426788],
+    [0.190303, 0.261644, 0.426329],
+    [0.195057, 0.264372, 0.425924],
+    [0.199764, 0.267099, 0.425497],
+    [0.204385, 0.269823, 0.425126],
+    [0.208926, 0.272546, 0.424809],
+    [0.213431, 0.275266, 0.424480],
+    [0.217863, 0.277985, 0.424206],
+    [0.222264, 0.280702, 0.423914],
+    [0.226598, 0.283419, 0.423678],
+    [0.230871, 0.286134, 0.423498],
+    [0.235120, 0.288848, 0.423304],
+    [0.239312, 0.291562, 0.423167],
+    [0.243485, 0.294274, 0.423014],
+    [0.247605, 0.296986, 0.422917],
+    [0.251675, 0.299698, 0.422873],
+    [0.255731, 0.302409, 0.422814],
+    [0.259740, 0.305120, 0.422810],
+    [0.263738, 0.307831, 0.422789],
+    [0.267693, 0.310542, 0.422821],
+    [0.271639, 0.313253, 0.422837],
+    [0.275513, 0.315965, 0.422979],
+    [0.279411, 0.318677, 0.423031],
+    [0.283240, 0.321390, 0.423211],
+    [0.287065, 0.324103, 0.423373],
+    [0.290884, 0.326816, 0.423517],
+    [0.294669, 0.329531, 0.423716],
+    [0.298421, 0.332247, 0.423973],
+    [0.302169, 0.334963, 0.424213],
+    [0.305886, 0.337681, 0.424512],
+    [0.309601, 0.340399, 0.424790],
+    [0.313287, 0.343120, 0.425120],
+    [0.316941, 0.345842, 0.425512],
+    [0.320595, 0.348565, 0.425889],
+    [0.324250, 0.351289, 0.426250],
+    [0.327875, 0.354016, 0.426670],
+    [0.331474, 0.356744, 0.427144],
+    [0.335073, 0.359474, 0.427605],
+    [0.338673, 0.362206, 0.428053],
+    [0.342246, 0.364939, 0.428559],
+    [0.345793, 0.367676, 0.429127],
+    [0.349341, 0.370414, 0.429685],
+    [0.352892, 0.373153, 0.430226],
+    [0.356418, 0.375896, 0.430823],
+    [0.359916, 0.378641, 0.431501],
+    [0.363446, 0.381388, 0.432075],
+    [0.366923, 0.384139, 0.432796],
+    [0.370430, 0.386890, 0.433428],
+    [0.373884, 0.389646, 0.434209],
+    [0.377371, 0.392404, 0.434890],
+    [0.380830, 0.395164, 0.435653],
+    [0.384268, 0.397928, 0.436475],
+    [0.387705, 0.400694, 0.437305],
+    [0.391151, 0.403464, 0.438096],
+    [0.394568, 0.406236, 0.438986],
+    [0.397991, 0.409011, 0.439848],
+    [0.401418, 0.411790, 0.440708],
+    [0.404820, 0.414572, 0.441642],
+    [0.408226, 0.417357, 0.442570],
+    [0.411607, 0.420145, 0.443577],
+    [0.414992, 0.422937, 0.444578],
+    [0.418383, 0.425733, 0.445560],
+    [0.421748, 0.428531, 0.446640],
+    [0.425120, 0.431334, 0.447692],
+    [0.428462, 0.434140, 0.448864],
+    [0.431817, 0.436950, 0.449982],
+    [0.435168, 0.439763, 0.451134],
+    [0.438504, 0.442580, 0.452341],
+    [0.441810, 0.445402, 0.453659],
+    [0.445148, 0.448226, 0.454885],
+    [0.448447, 0.451053, 0.456264],
+    [0.451759, 0.453887, 0.457582],
+    [0.455072, 0.456718, 0.458976],
+    [0.458366, 0.459552, 0.460457],
+    [0.461616, 0.462405, 0.461969],
+    [0.464947, 0.465241, 0.463395],
+    [0.468254, 0.468083, 0.464908],
+    [0.471501, 0.470960, 0.466357],
+    [0.474812, 0.473832, 0.467681],
+    [0.478186, 0.476699, 0.468845],
+    [0.481622, 0.479573, 0.469767],
+    [0.485141, 0.482451, 0.470384],
+    [0.488697, 0.485318, 0.471008],
+    [0.492278, 0.488198, 0.471453],
+    [0.495913, 0.491076, 0.471751],
+    [0.499552, 0.493960, 0.472032],
+    [0.503185, 0.496851, 0.472305],
+    [0.506866, 0.499743, 0.472432],
+    [0.510540, 0.502643, 0.472550],
+    [0.514226, 0.505546, 0.472640],
+    [0.517920, 0.508454, 0.472707],
+    [0.521643, 0.511367, 0.472639],
+    [0.525348, 0.514285, 0.472660],
+    [0.529086, 0.517207, 0.472543],
+    [0.532829, 0.520135, 0.472401],
+    [0.536553, 0.523067, 0.472352],
+    [0.540307, 0.526005, 0.472163],
+    [0.544069, 0.528948, 0.471947],
+    [0.547840, 0.531895, 0.471704],
+    [0.551612, 0.534849, 0.471439],
+    [0.555393, 0.537807, 0.471147],
+    [0.559181, 0.540771, 0.470829],
+    [0.562972, 0.543741, 0.470488],
+    [0.566802, 0.546715, 0.469988],
+    [0.570607, 0.549695, 0.469593],
+    [0.574417, 0.552682, 0.469172],
+    [0.578236, 0.555673, 0.468724],
+    [0.582087, 0.558670, 0.468118],
+    [0.585916, 0.561674, 0.467618],
+    [0.589753, 0.564682, 0.467090],
+    [0.593622, 0.567697, 0.466401],
+    [0.597469, 0.570718, 0.465821],
+    [0.601354, 0.573743, 0.465074],
+    [0.605211, 0.576777, 0.464441],
+    [0.609105, 0.579816, 0.463638],
+    [0.612977, 0.582861, 0.462950],
+    [0.616852, 0.585913, 0.462237],
+    [0.620765, 0.588970, 0.461351],
+    [0.624654, 0.592034, 0.460583],
+    [0.628576, 0.595104, 0.459641],
+    [0.632506, 0.598180, 0.458668],
+    [0.636412, 0.601264, 0.457818],
+    [0.640352, 0.604354, 0.456791],
+    [0.644270, 0.607450, 0.455886],
+    [0.648222, 0.610553, 0.454801],
+    [0.652178, 0.613664, 0.453689],
+    [0.656114, 0.616780, 0.452702],
+    [0.660082, 0.619904, 0.451534],
+    [0.664055, 0.623034, 0.450338],
+    [0.668008, 0.626171, 0.449270],
+    [0.671991, 0.629316, 0.448018],
+    [0.675981, 0.632468, 0.446736],
+    [0.679979, 0.635626, 0.445424],
+    [0.683950, 0.638793, 0.444251],
+    [0.687957, 0.641966, 0.442886],
+    [0.691971, 0.645145, 0.441491],
+    [0.695985, 0.648334, 0.440072],
+    [0.700008, 0.651529, 0.438624],
+    [0.704037, 0.654731, 0.437147],
+    [0.708067, 0.657942, 0.435647],
+    [0.712105, 0.661160, 0.434117],
+    [0.716177, 0.664384, 0.432386],
+    [0.720222, 0.667618, 0.430805],
+    [0.724274, 0.670859, 0.429194],
+    [0.728334, 0.674107, 0.427554],
+    [0.732422, 0.677364, 0.425717],
+    [0.736488, 0.680629, 0.424028],
+    [0.740589, 0.683900, 0.422131],
+    [0.744664, 0.687181, 0.420393],
+    [0.748772, 0.690470, 0.418448],
+    [0.752886, 0.693766, 0.416472],
+    [0.756975, 0.697071, 0.414659],
+    [0.761096, 0.700384, 0.412638],
+    [0.765223, 0.703705, 0.410587],
+    [0.769353, 0.707035, 0.408516],
+    [0.773486, 0.710373, 0.406422],
+    [0.777651, 0.713719, 0.404112],
+    [0.781795, 0.717074, 0.401966],
+    [0.785965, 0.720438, 0.399613],
+    [0.790116, 0.723810, 0.397423],
+    [0.794298, 0.727190, 0.395016],
+    [0.798480, 0.730580, 0.392597],
+    [0.802667, 0.733978, 0.390153],
+    [0.806859, 0.737385, 0.387684],
+    [0.811054, 0.740801, 0.385198],
+    [0.815274, 0.744226, 0.382504],
+    [0.819499, 0.747659, 0.379785],
+    [0.823729, 0.751101, 0.377043],
+    [0.827959, 0.754553, 0.374292],
+    [0.832192, 0.758014, 0.371529],
+    [0.836429, 0.761483, 0.368747],
+    [0.840693, 0.764962, 0.365746],
+    [0.844957, 0.768450, 0.362741],
+    [0.849223, 0.771947, 0.359729],
+    [0.853515, 0.775454, 0.356500],
+    [0.857809, 0.778969, 0.353259],
+    [0.862105, 0.782494, 0.350011],
+    [0.866421, 0.786028, 0.346571],
+    [0.870717, 0.789572, 0.343333],
+    [0.875057, 0.793125, 0.339685],
+    [0.879378, 0.796687, 0.336241],
+    [0.883720, 0.800258, 0.332599],
+    [0.888081, 0.803839, 0.328770],
+    [0.892440, 0.807430, 0.324968],
+    [0.896818, 0.811030, 0.320982],
+    [0.901195, 0.814639, 0.317021],
+    [0.905589, 0.818257, 0.312889],
+    [0.910000, 0.821885, 0.308594],
+    [0.914407, 0.825522, 0.304348],
+    [0.918828, 0.829168, 0.299960],
+    [0.923279, 0.832822, 0.295244],
+    [0.927724, 0.836486, 0.290611],
+    [0.932180, 0.840159, 0.285880],
+    [0.936660, 0.843841, 0.280876],
+    [0.941147, 0.847530, 0.275815],
+    [0.945654, 0.851228, 0.270532],
+    [0.950178, 0.854933, 0.265085],
+    [0.954725, 0.858646, 0.259365],
+    [0.959284, 0.862365, 0.253563],
+    [0.963872, 0.866089, 0.247445],
+    [0.968469, 0.869819, 0.241310],
+    [0.973114, 0.873550, 0.234677],
+    [0.977780, 0.877281, 0.227954],
+    [0.982497, 0.881008, 0.220878],
+    [0.987293, 0.884718, 0.213336],
+    [0.992218, 0.888385, 0.205468],
+    [0.994847, 0.892954, 0.203445],
+    [0.995249, 0.898384, 0.207561],
+    [0.995503, 0.903866, 0.212370],
+    [0.995737, 0.909344, 0.217772],
+];
+
+/// coarse approximation of matplotlib's cyclic twilight by nine colors interpolated in CIELAB.
+/// It is not matplotlib's original 510-entry table. The first and the last colors are the same
+pub const COLORMAP_TWILIGHT: [[f32; 3]; 9] = [
+    [0.8857, 0.8500, 0.8878],
+    [0.5979, 0.6761, 0.7897],
+    [0.3775, 0.4569, 0.7506],
+    [0.3606, 0.2376, 0.5881],
+    [0.1857, 0.0725, 0.2355],
+    [0.4590, 0.1545, 0.3508],
+    [0.6730, 0.3455, 0.3224],
+    [0.7901, 0.6180, 0.5402],
+    [0.8857, 0.8500, 0.8878],
+];
+
+/// ColorBrewer's diverging RdBu with 11 classes (red for the low values as in matplotlib)
+pub const COLORMAP_RDBU: [[f32; 3]; 11] = table_from_hex([
+    0x67001f, 0xb2182b, 0xd6604d, 0xf4a582, 0xfddbc7, 0xf7f7f7, 0xd1e5f0, 0x92c5de, 0x4393c3,
+    0x2166ac, 0x053061,
+]);
+
+/// ColorBrewer's diverging Spectral with 11 classes
+pub const COLORMAP_SPECTRAL: [[f32; 3]; 11] = table_from_hex([
+    0x9e0142, 0xd53e4f, 0xf46d43, 0xfdae61, 0xfee08b, 0xffffbf, 0xe6f598, 0xabdda4, 0x66c2a5,
+    0x3288bd, 0x5e4fa2,
+]);
+
+/// matplotlib's (Tableau's) qualitative palette of 10 categories
+pub const PALETTE_TAB10: [[f32; 3]; 10] = table_from_hex([
+    0x1f77b4, 0xff7f0e, 0x2ca02c, 0xd62728, 0x9467bd, 0x8c564b, 0xe377c2, 0x7f7f7f, 0xbcbd22,
+    0x17becf,
+]);
+
+/// ColorBrewer's qualitative palette Set1 of 9 categories
+pub const PALETTE_SET1: [[f32; 3]; 9] = table_from_hex([
+    0xe41a1c, 0x377eb8, 0x4daf4a, 0x984ea3, 0xff7f00, 0xffff33, 0xa65628, 0xf781bf, 0x999999,
+]);
+
 pub fn apply_colormap<T, const N: usize>(
     p: T,
     pmin: T,
@@ -578,3 +1670,572 @@ where
         colormap[ic][2] + (colormap[ic + 1][2] - colormap[ic][2]) * ratio,
     ]
 }
+
+/// color space in which the colors of `Colormap` are interpolated
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColorSpace {
+    /// gamma encoded sRGB (same as `apply_colormap`)
+    Srgb,
+    LinearRgb,
+    /// CIE L*a*b*
+    Lab,
+    Oklab,
+}
+
+impl ColorSpace {
+    /// from sRGB to this color space
+    fn encode(&self, c: &[f32; 3]) -> [f32; 3] {
+        use crate::color::*;
+        match self {
+            ColorSpace::Srgb => *c,
+            ColorSpace::LinearRgb => c.map(linear_from_srgb),
+            ColorSpace::Lab => {
+                let (l, a, b) = lab_from_srgb(c[0], c[1], c[2]);
+                [l, a, b]
+            }
+            ColorSpace::Oklab => {
+                let [r, g, b] = c.map(linear_from_srgb);
+                let (l, a, b) = oklab_from_linear_rgb(r, g, b);
+                [l, a, b]
+            }
+        }
+    }
+
+    /// from this color space to sRGB
+    fn decode(&self, c: &[f32; 3]) -> [f32; 3] {
+        use crate::color::*;
+        let encode = |v: f32| srgb_from_linear(v.clamp(0., 1.));
+        match self {
+            ColorSpace::Srgb => *c,
+            ColorSpace::LinearRgb => c.map(encode),
+            ColorSpace::Lab => {
+                let (r, g, b) = srgb_from_lab(c[0], c[1], c[2]);
+                [r, g, b]
+            }
+            ColorSpace::Oklab => {
+                let (r, g, b) = linear_rgb_from_oklab(c[0], c[1], c[2]);
+                [r, g, b].map(encode)
+            }
+        }
+    }
+}
+
+/// colormap made of the control points
+/// ```
+/// use del_canvas::colormap::{Colormap, ColorSpace};
+/// let mut cmap = Colormap::from_points(&[(0.0, [0., 0., 1.]), (0.3, [1., 1., 1.]), (1.0, [1., 0., 0.])]).unwrap();
+/// cmap.space = ColorSpace::Oklab;
+/// cmap.over = Some([1., 0., 1.]);
+/// assert_eq!(cmap.apply(2.0, 0.0, 1.0), [1., 0., 1.]);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Colormap {
+    /// `(position, sRGB color)` with the positions increasing from `0` to `1`
+    pub points: Vec<(f32, [f32; 3])>,
+    pub space: ColorSpace,
+    /// color for the value below the range. The first color is used if `None`
+    pub under: Option<[f32; 3]>,
+    /// color for the value above the range. The last color is used if `None`
+    pub over: Option<[f32; 3]>,
+    /// color for NaN
+    pub nan: [f32; 3],
+    /// quantize the colormap into the bins of the same width if `Some`.
+    /// The colors of the first and the last bins are the both ends of the colormap
+    pub num_bins: Option<usize>,
+}
+
+impl Colormap {
+    /// the colors are placed at the equal intervals
+    pub fn from_colors(colors: &[[f32; 3]]) -> Self {
+        assert!(!colors.is_empty());
+        let num_div = (colors.len() - 1).max(1) as f32;
+        let points = colors
+            .iter()
+            .enumerate()
+            .map(|(i, &c)| (i as f32 / num_div, c))
+            .collect();
+        Colormap {
+            points,
+            space: ColorSpace::Srgb,
+            under: None,
+            over: None,
+            nan: [0.; 3],
+            num_bins: None,
+        }
+    }
+
+    /// * `points` - `(position, sRGB color)`. The positions need to be non-decreasing and
+    ///   are normalized such that the first is `0` and the last is `1`
+    pub fn from_points(points: &[(f32, [f32; 3])]) -> anyhow::Result<Self> {
+        anyhow::ensure!(!points.is_empty(), "no control points");
+        anyhow::ensure!(
+            points.windows(2).all(|w| w[0].0 <= w[1].0),
+            "the positions of the control points are not sorted"
+        );
+        let (p0, p1) = (points[0].0, points[points.len() - 1].0);
+        let scale = if p1 > p0 { 1. / (p1 - p0) } else { 1. };
+        let mut cmap = Self::from_colors(&[[0.; 3]]);
+        cmap.points = points.iter().map(|&(p, c)| ((p - p0) * scale, c)).collect();
+        Ok(cmap)
+    }
+
+    /// the colormap flipped upside down. `under` and `over` are swapped
+    pub fn reversed(&self) -> Self {
+        Colormap {
+            points: self
+                .points
+                .iter()
+                .rev()
+                .map(|&(p, c)| (1. - p, c))
+                .collect(),
+            under: self.over,
+            over: self.under,
+            ..self.clone()
+        }
+    }
+
+    /// sRGB color at the normalized value `t` in `[0, 1]`
+    pub fn eval(&self, t: f32) -> [f32; 3] {
+        if t.is_nan() {
+            return self.nan;
+        }
+        if t < 0. {
+            return self.under.unwrap_or(self.points[0].1);
+        }
+        if t > 1. {
+            return self.over.unwrap_or(self.points[self.points.len() - 1].1);
+        }
+        let t = match self.num_bins {
+            // the colors of the bins include both ends of the colormap
+            Some(num_bins) if num_bins > 1 => {
+                let i_bin = ((t * num_bins as f32) as usize).min(num_bins - 1);
+                i_bin as f32 / (num_bins - 1) as f32
+            }
+            _ => t,
+        };
+        let i_seg = self.points.partition_point(|&(p, _)| p <= t);
+        if i_seg == 0 {
+            return self.points[0].1;
+        }
+        if i_seg == self.points.len() {
+            return self.points[i_seg - 1].1;
+        }
+        let (p0, c0) = self.points[i_seg - 1];
+        let (p1, c1) = self.points[i_seg];
+        let r = if p1 > p0 { (t - p0) / (p1 - p0) } else { 0. };
+        let c0 = self.space.encode(&c0);
+        let c1 = self.space.encode(&c1);
+        let c: [f32; 3] = std::array::from_fn(|i| c0[i] + (c1[i] - c0[i]) * r);
+        self.space.decode(&c)
+    }
+
+    /// sRGB color of the value `p` in the range `[pmin, pmax]` (same as `apply_colormap`)
+    pub fn apply<T>(&self, p: T, pmin: T, pmax: T) -> [f32; 3]
+    where
+        T: num_traits::Float + num_traits::AsPrimitive<f32>,
+    {
+        self.eval(((p - pmin) / (pmax - pmin)).as_())
+    }
+
+    /// lookup table of `num` colors sampled at the equal intervals
+    pub fn sample(&self, num: usize) -> Vec<[f32; 3]> {
+        let num_div = (num.max(2) - 1) as f32;
+        (0..num).map(|i| self.eval(i as f32 / num_div)).collect()
+    }
+}
+
+#[test]
+fn test_colormap() {
+    let near =
+        |a: [f32; 3], b: [f32; 3]| a.iter().zip(b.iter()).all(|(a, b)| (a - b).abs() < 1.0e-4);
+    let cmap = Colormap::from_colors(&COLORMAP_JET);
+    for i in 0..=20 {
+        let p = -0.5 + 2.0 * i as f32 / 20.;
+        assert!(near(
+            cmap.apply(p, 0., 1.),
+            apply_colormap(p, 0., 1., COLORMAP_JET)
+        ));
+    }
+    assert_eq!(cmap.eval(f32::NAN), [0.; 3]);
+    assert!(near(cmap.reversed().eval(0.2), cmap.eval(0.8)));
+    // gray ramp
+    let mut cmap = Colormap::from_colors(&[[0.; 3], [1.; 3]]);
+    cmap.space = ColorSpace::LinearRgb;
+    assert!((cmap.eval(0.5)[0] - 0.7354).abs() < 1.0e-3);
+    cmap.space = ColorSpace::Lab;
+    let c = cmap.eval(0.5);
+    assert!((crate::color::lab_from_srgb(c[0], c[1], c[2]).0 - 50.).abs() < 1.0e-2);
+    cmap.space = ColorSpace::Oklab;
+    let c = cmap.eval(0.25);
+    let [r, g, b] = c.map(crate::color::linear_from_srgb);
+    assert!((crate::color::oklab_from_linear_rgb(r, g, b).0 - 0.25).abs() < 1.0e-4);
+    // bins, under and over
+    cmap.space = ColorSpace::Srgb;
+    cmap.num_bins = Some(4);
+    cmap.under = Some([0., 0., 1.]);
+    assert_eq!(cmap.eval(0.1), [0.; 3]);
+    assert!(near(cmap.eval(0.3), [1. / 3.; 3]));
+    assert_eq!(cmap.eval(1.0), [1.; 3]);
+    assert_eq!(cmap.eval(-0.1), [0., 0., 1.]);
+    assert_eq!(cmap.eval(1.1), [1.; 3]);
+    assert_eq!(cmap.sample(3).len(), 3);
+    // control points at arbitrary positions
+    let cmap = Colormap::from_points(&[(-1., [0.; 3]), (0., [0.5; 3]), (3., [1.; 3])]).unwrap();
+    assert!(near(cmap.eval(0.25), [0.5; 3]));
+    assert!(Colormap::from_points(&[(1., [0.; 3]), (0., [1.; 3])]).is_err());
+}
+
+// ---------------------------
+// diverging colormap by K. Moreland, "Diverging Color Maps for Scientific Visualization", 2009
+
+fn msh_from_srgb(c: &[f32; 3]) -> [f32; 3] {
+    let (l, a, b) = crate::color::lab_from_srgb(c[0] as f64, c[1] as f64, c[2] as f64);
+    let m = (l * l + a * a + b * b).sqrt();
+    let s = if m > 0. { (l / m).acos() } else { 0. };
+    [m as f32, s as f32, b.atan2(a) as f32]
+}
+
+fn srgb_from_msh(msh: &[f32; 3]) -> [f32; 3] {
+    let [m, s, h] = msh.map(|v| v as f64);
+    let (r, g, b) =
+        crate::color::srgb_from_lab(m * s.cos(), m * s.sin() * h.cos(), m * s.sin() * h.sin());
+    [r as f32, g as f32, b as f32]
+}
+
+/// hue of the unsaturated color to be interpolated with the saturated color `msh`
+fn adjust_hue(msh: &[f32; 3], m_unsat: f32) -> f32 {
+    let [m, s, h] = *msh;
+    if m >= m_unsat {
+        return h;
+    }
+    let h_spin = s * (m_unsat * m_unsat - m * m).sqrt() / (m * s.sin());
+    if h > -std::f32::consts::FRAC_PI_3 {
+        h + h_spin
+    } else {
+        h - h_spin
+    }
+}
+
+/// interpolate the two sRGB colors in the Msh space passing through white in the middle
+/// if the colors are saturated and distinct
+pub fn diverging_msh(c0: &[f32; 3], c1: &[f32; 3], t: f32) -> [f32; 3] {
+    let mut msh0 = msh_from_srgb(c0);
+    let mut msh1 = msh_from_srgb(c1);
+    let mut t = t.clamp(0., 1.);
+    let dh = (msh0[2] - msh1[2]).abs();
+    if msh0[1] > 0.05 && msh1[1] > 0.05 && dh > std::f32::consts::FRAC_PI_3 {
+        let m_mid = msh0[0].max(msh1[0]).max(88.);
+        if t < 0.5 {
+            msh1 = [m_mid, 0., 0.];
+            t *= 2.;
+        } else {
+            msh0 = [m_mid, 0., 0.];
+            t = 2. * t - 1.;
+        }
+    }
+    if msh0[1] < 0.05 && msh1[1] > 0.05 {
+        msh0[2] = adjust_hue(&msh1, msh0[0]);
+    } else if msh1[1] < 0.05 && msh0[1] > 0.05 {
+        msh1[2] = adjust_hue(&msh0, msh1[0]);
+    }
+    let msh: [f32; 3] = std::array::from_fn(|i| msh0[i] + (msh1[i] - msh0[i]) * t);
+    srgb_from_msh(&msh)
+}
+
+/// Moreland's cool-to-warm diverging map sampled at 33 points
+pub fn colormap_coolwarm() -> Colormap {
+    let c0 = [59. / 255., 76. / 255., 192. / 255.];
+    let c1 = [180. / 255., 4. / 255., 38. / 255.];
+    let colors: Vec<[f32; 3]> = (0..33)
+        .map(|i| diverging_msh(&c0, &c1, i as f32 / 32.))
+        .collect();
+    Colormap::from_colors(&colors)
+}
+
+/// colormap by the name used in matplotlib (case-insensitive), e.g., `"viridis"` or `"RdBu"`.
+/// The suffix `_r` gives the reversed map. The qualitative palettes (`tab10`, `set1`) are discrete
+pub fn colormap_by_name(name: &str) -> Option<Colormap> {
+    let lower = name.to_ascii_lowercase();
+    if let Some(base) = lower.strip_suffix("_r") {
+        return colormap_by_name(base).map(|cmap| cmap.reversed());
+    }
+    let cmap = match lower.as_str() {
+        "heat" => Colormap::from_colors(&COLORMAP_HEAT),
+        "bwr" => Colormap::from_colors(&COLORMAP_BWR),
+        "hot" => Colormap::from_colors(&COLORMAP_HOT),
+        "jet" => Colormap::from_colors(&COLORMAP_JET),
+        "plasma" => Colormap::from_colors(&COLORMAP_PLASMA),
+        "viridis" => Colormap::from_colors(&COLORMAP_VIRIDIS),
+        "magma" => Colormap::from_colors(&COLORMAP_MAGMA),
+        "inferno" => Colormap::from_colors(&COLORMAP_INFERNO),
+        "turbo" => Colormap::from_colors(&COLORMAP_TURBO),
+        "cividis" => Colormap::from_colors(&COLORMAP_CIVIDIS),
+        "twilight" => {
+            let mut cmap = Colormap::from_colors(&COLORMAP_TWILIGHT);
+            cmap.space = ColorSpace::Lab;
+            cmap
+        }
+        "coolwarm" => colormap_coolwarm(),
+        "rdbu" => Colormap::from_colors(&COLORMAP_RDBU),
+        "spectral" => Colormap::from_colors(&COLORMAP_SPECTRAL),
+        "tab10" => {
+            let mut cmap = Colormap::from_colors(&PALETTE_TAB10);
+            cmap.num_bins = Some(PALETTE_TAB10.len());
+            cmap
+        }
+        "set1" => {
+            let mut cmap = Colormap::from_colors(&PALETTE_SET1);
+            cmap.num_bins = Some(PALETTE_SET1.len());
+            cmap
+        }
+        _ => return None,
+    };
+    Some(cmap)
+}
+
+#[test]
+fn test_colormap_catalogue() {
+    let near = |a: [f32; 3], b: [f32; 3], eps: f32| {
+        a.iter().zip(b.iter()).all(|(a, b)| (a - b).abs() < eps)
+    };
+    // the values in the original tables
+    assert_eq!(COLORMAP_MAGMA[0], [0.001462, 0.000466, 0.013866]);
+    assert_eq!(COLORMAP_MAGMA[128], [0.716387, 0.214982, 0.475290]);
+    assert_eq!(COLORMAP_MAGMA[255], [0.987053, 0.991438, 0.749504]);
+    assert_eq!(COLORMAP_INFERNO[128], [0.735683, 0.215906, 0.330245]);
+    assert_eq!(COLORMAP_INFERNO[255], [0.988362, 0.998364, 0.644924]);
+    assert_eq!(COLORMAP_TURBO[0], [0.18995, 0.07176, 0.23217]);
+    assert_eq!(COLORMAP_TURBO[255], [0.47960, 0.01583, 0.01055]);
+    assert_eq!(COLORMAP_CIVIDIS[0], [0.000000, 0.135112, 0.304751]);
+    assert_eq!(COLORMAP_CIVIDIS[255], [0.995737, 0.909344, 0.217772]);
+    // the tables are smooth (i.e., no entry is mistyped)
+    for table in [
+        &COLORMAP_PLASMA,
+        &COLORMAP_VIRIDIS,
+        &COLORMAP_MAGMA,
+        &COLORMAP_INFERNO,
+        &COLORMAP_TURBO,
+        &COLORMAP_CIVIDIS,
+    ] {
+        for w in table.windows(3) {
+            for i_ch in 0..3 {
+                let d2 = w[0][i_ch] - 2. * w[1][i_ch] + w[2][i_ch];
+                assert!(d2.abs() < 0.015, "{:?}", w);
+            }
+        }
+    }
+    // Moreland's table
+    let cmap = colormap_coolwarm();
+    assert!(near(
+        cmap.eval(0.0),
+        [59. / 255., 76. / 255., 192. / 255.],
+        1.0e-3
+    ));
+    assert!(near(
+        cmap.eval(0.25),
+        [141. / 255., 176. / 255., 254. / 255.],
+        0.01
+    ));
+    assert!(near(cmap.eval(0.5), [221. / 255.; 3], 0.01));
+    assert!(near(
+        cmap.eval(0.75),
+        [244. / 255., 154. / 255., 123. / 255.],
+        0.01
+    ));
+    //
+    let names = [
+        "heat", "bwr", "hot", "jet", "plasma", "viridis", "magma", "inferno", "turbo", "cividis",
+        "twilight", "coolwarm", "RdBu", "Spectral", "tab10", "Set1",
+    ];
+    for name in names {
+        assert!(colormap_by_name(name).is_some(), "{}", name);
+    }
+    let cmap = colormap_by_name("viridis_r").unwrap();
+    assert_eq!(cmap.eval(0.0), COLORMAP_VIRIDIS[255]);
+    let cmap = colormap_by_name("tab10").unwrap();
+    assert_eq!(cmap.eval(0.15), PALETTE_TAB10[1]);
+    assert!(colormap_by_name("unknown").is_none());
+    let twilight = colormap_by_name("twilight").unwrap();
+    assert!(near(twilight.eval(0.0), twilight.eval(1.0), 1.0e-5));
+}
+
+// ---------------------------
+// colormapping the scalar image
+
+/// range of the values mapped to the both ends of the colormap
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ValueRange {
+    Explicit {
+        min: f32,
+        max: f32,
+    },
+    /// minimum and maximum of the finite values (the positive values for `Scale::Log`)
+    Auto,
+    /// clip the outliers at the percentiles in `[0, 100]` (e.g., `low: 2., high: 98.`)
+    Percentile {
+        low: f32,
+        high: f32,
+    },
+    /// `[-a, a]` where `a` is the maximum absolute value so that zero is at the center
+    Symmetric,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Scale {
+    Linear,
+    /// logarithmic scale. The non-positive values are below the range
+    Log,
+    /// `((v - min) / (max - min))^gamma`
+    Power(f32),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Normalization {
+    pub range: ValueRange,
+    pub scale: Scale,
+}
+
+impl Default for Normalization {
+    fn default() -> Self {
+        Normalization {
+            range: ValueRange::Auto,
+            scale: Scale::Linear,
+        }
+    }
+}
+
+impl Normalization {
+    /// the actual range `(min, max)` for the values. `(0, 1)` if there is no valid value
+    pub fn resolve_range<T>(&self, vals: &[T]) -> (f32, f32)
+    where
+        T: num_traits::Float + num_traits::AsPrimitive<f32>,
+    {
+        let is_log = self.scale == Scale::Log;
+        let valid = || {
+            vals.iter()
+                .map(|v| v.as_())
+                .filter(move |v: &f32| v.is_finite() && (!is_log || *v > 0.))
+        };
+        let range = match self.range {
+            ValueRange::Explicit { min, max } => Some((min, max)),
+            ValueRange::Auto => valid().fold(None, |r, v| match r {
+                None => Some((v, v)),
+                Some((min, max)) => Some((v.min(min), v.max(max))),
+            }),
+            ValueRange::Percentile { low, high } => {
+                let mut vals: Vec<f32> = valid().collect();
+                vals.sort_by(|a, b| a.total_cmp(b));
+                let percentile = |p: f32| {
+                    let x = (p.clamp(0., 100.) / 100.) * (vals.len() - 1) as f32;
+                    let i = (x.floor() as usize).min(vals.len() - 1);
+                    let j = (i + 1).min(vals.len() - 1);
+                    vals[i] + (vals[j] - vals[i]) * (x - i as f32)
+                };
+                if vals.is_empty() {
+                    None
+                } else {
+                    Some((percentile(low), percentile(high)))
+                }
+            }
+            ValueRange::Symmetric => valid().map(|v| v.abs()).reduce(f32::max).map(|a| (-a, a)),
+        };
+        range.unwrap_or((0., 1.))
+    }
+
+    /// map the value into `[0, 1]` for the range `(min, max)`.
+    /// The result is out of `[0, 1]` for the values out of the range and NaN for NaN
+    pub fn normalize(&self, v: f32, range: (f32, f32)) -> f32 {
+        let (min, max) = range;
+        let (v, min, max) = match self.scale {
+            Scale::Log => {
+                if v <= 0. {
+                    return -1.;
+                }
+                (v.ln(), min.max(f32::MIN_POSITIVE).ln(), max.ln())
+            }
+            _ => (v, min, max),
+        };
+        if max <= min {
+            return if v.is_nan() { v } else { 0.5 };
+        }
+        let t = (v - min) / (max - min);
+        match self.scale {
+            Scale::Power(gamma) if t > 0. => t.powf(gamma),
+            _ => t,
+        }
+    }
+}
+
+/// colormap the scalar image into the sRGB image `[r0, g0, b0, r1, ...]` in `[0, 1]`
+/// that can be saved by `write_png_from_float_image` with the depth of three.
+/// The NaN pixels are painted with `colormap.nan`.
+/// Returns the image and the range `(min, max)` used for the normalization (e.g., for the colorbar)
+pub fn rgb_image_from_scalar_image<T>(
+    vals: &[T],
+    normalization: &Normalization,
+    colormap: &Colormap,
+) -> (Vec<f32>, (f32, f32))
+where
+    T: num_traits::Float + num_traits::AsPrimitive<f32>,
+{
+    let range = normalization.resolve_range(vals);
+    let img = vals
+        .iter()
+        .flat_map(|v| colormap.eval(normalization.normalize(v.as_(), range)))
+        .collect();
+    (img, range)
+}
+
+#[test]
+fn test_rgb_image_from_scalar_image() -> anyhow::Result<()> {
+    let img_shape = (128usize, 64usize);
+    // Gaussian bump with the noise and NaN hole
+    let vals: Vec<f64> = (0..img_shape.0 * img_shape.1)
+        .map(|i_pix| {
+            let (x, y) = ((i_pix % img_shape.0) as f64, (i_pix / img_shape.0) as f64);
+            let r2 = (x - 64.).powi(2) + (y - 32.).powi(2);
+            if r2 < 16. {
+                return f64::NAN;
+            }
+            (-r2 / 500.).exp() - 0.2 + if i_pix % 101 == 0 { 10. } else { 0. }
+        })
+        .collect();
+    let mut colormap = colormap_by_name("viridis").unwrap();
+    colormap.nan = [1., 0., 0.];
+    let (img, range) = rgb_image_from_scalar_image(&vals, &Normalization::default(), &colormap);
+    assert_eq!(img.len(), vals.len() * 3);
+    assert!(range.1 > 9.0); // affected by the outliers
+    assert_eq!(
+        &img[(32 * 128 + 64) * 3..(32 * 128 + 64) * 3 + 3],
+        &[1., 0., 0.]
+    );
+    //
+    let norm = Normalization {
+        range: ValueRange::Percentile { low: 1., high: 99. },
+        ..Default::default()
+    };
+    let (img, range) = rgb_image_from_scalar_image(&vals, &norm, &colormap);
+    assert!(range.1 < 1.0);
+    crate::write_png_from_float_image("target/scalar_percentile.png", img_shape, 3, &img)?;
+    //
+    let norm = Normalization {
+        range: ValueRange::Symmetric,
+        ..Default::default()
+    };
+    assert_eq!(norm.resolve_range(&[-1f32, 3., f32::NAN]), (-3., 3.));
+    assert_eq!(norm.normalize(0., (-3., 3.)), 0.5);
+    let norm = Normalization {
+        range: ValueRange::Auto,
+        scale: Scale::Log,
+    };
+    let range = norm.resolve_range(&[-1f32, 0.1, 1000.]);
+    assert_eq!(range, (0.1, 1000.));
+    assert!((norm.normalize(10., range) - 0.5).abs() < 1.0e-5);
+    assert!(norm.normalize(-1., range) < 0.);
+    let norm = Normalization {
+        range: ValueRange::Explicit { min: 0., max: 2. },
+        scale: Scale::Power(2.),
+    };
+    assert_eq!(norm.normalize(1., norm.resolve_range::<f32>(&[])), 0.25);
+    Ok(())
+}
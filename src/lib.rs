@@ -1,13 +1,19 @@
+pub mod blend;
 pub mod cam2;
 pub mod cam3;
+pub mod canvas;
+pub mod canvas_apng;
 pub mod canvas_bitmap;
 pub mod canvas_gif;
 pub mod canvas_svg;
 pub mod color;
+pub mod colorbar;
 pub mod colormap;
 pub mod image_interpolation;
 pub mod morphology;
+pub mod quantize;
 pub mod rasterize;
+pub mod svg;
 
 use num_traits::{AsPrimitive, NumCast};
 
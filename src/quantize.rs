@@ -0,0 +1,182 @@
+//! color quantization of the RGB images for the palette-based formats such as GIF
+
+/// palette of at most `num_colors` colors computed by the median-cut algorithm.
+/// The box with the widest channel range is split at the median of the pixels repeatedly,
+/// and each color is the mean of the pixels in the box.
+pub fn median_cut(pixels: &[[u8; 3]], num_colors: usize) -> Vec<[u8; 3]> {
+    // histogram of the unique colors
+    let mut hist = std::collections::HashMap::<[u8; 3], usize>::new();
+    for &p in pixels {
+        *hist.entry(p).or_insert(0) += 1;
+    }
+    let mut hist: Vec<([u8; 3], usize)> = hist.into_iter().collect();
+    hist.sort_unstable();
+    if hist.is_empty() || num_colors == 0 {
+        return vec![];
+    }
+    // channel with the widest range and its width
+    let widest = |colors: &[([u8; 3], usize)]| {
+        (0..3)
+            .map(|i_ch| {
+                let min = colors.iter().map(|c| c.0[i_ch]).min().unwrap();
+                let max = colors.iter().map(|c| c.0[i_ch]).max().unwrap();
+                (i_ch, max - min)
+            })
+            .max_by_key(|&(_, w)| w)
+            .unwrap()
+    };
+    let mut boxes = vec![hist];
+    while boxes.len() < num_colors {
+        let Some((i_box, (i_ch, _))) = boxes
+            .iter()
+            .enumerate()
+            .map(|(i_box, b)| (i_box, widest(b)))
+            .filter(|(_, (_, w))| *w > 0)
+            .max_by_key(|(_, (_, w))| *w)
+        else {
+            break; // every box has a single color
+        };
+        let mut colors = boxes.swap_remove(i_box);
+        colors.sort_unstable_by_key(|c| c.0[i_ch]);
+        let num_pix: usize = colors.iter().map(|c| c.1).sum();
+        let mut acc = 0;
+        let mut i_split = colors
+            .iter()
+            .position(|c| {
+                acc += c.1;
+                acc * 2 >= num_pix
+            })
+            .unwrap()
+            + 1;
+        // both halves need at least one color
+        i_split = i_split.clamp(1, colors.len() - 1);
+        let upper = colors.split_off(i_split);
+        boxes.push(colors);
+        boxes.push(upper);
+    }
+    boxes
+        .iter()
+        .map(|colors| {
+            let num_pix: usize = colors.iter().map(|c| c.1).sum();
+            std::array::from_fn(|i_ch| {
+                let sum: usize = colors.iter().map(|c| c.0[i_ch] as usize * c.1).sum();
+                ((sum + num_pix / 2) / num_pix) as u8
+            })
+        })
+        .collect()
+}
+
+/// index of the palette color nearest to `c` in the squared RGB distance
+fn nearest(palette: &[[u8; 3]], c: &[f32; 3]) -> usize {
+    let dist = |p: &[u8; 3]| -> f32 { (0..3).map(|i| (p[i] as f32 - c[i]).powi(2)).sum() };
+    (0..palette.len())
+        .min_by(|&i, &j| dist(&palette[i]).total_cmp(&dist(&palette[j])))
+        .unwrap_or(0)
+}
+
+/// replace the colors by the indices of the nearest colors in the palette
+/// * `palette` - from one to 256 colors
+/// * `dither` - diffuse the quantization error with the Floyd–Steinberg dithering
+/// * `is_transparent` - pixels excluded from the quantization. They get the index `0`
+///   and the dithering error is neither diffused from nor into them
+pub fn palette_indices(
+    img_data: &[[u8; 3]],
+    width: usize,
+    palette: &[[u8; 3]],
+    dither: bool,
+    is_transparent: Option<&[bool]>,
+) -> anyhow::Result<Vec<u8>> {
+    anyhow::ensure!(
+        !palette.is_empty() && palette.len() <= 256,
+        "the palette needs 1 to 256 colors but has {}",
+        palette.len()
+    );
+    if let Some(is_transparent) = is_transparent {
+        anyhow::ensure!(
+            is_transparent.len() == img_data.len(),
+            "the size of the transparency mask does not match the image"
+        );
+    }
+    let is_skipped = |i_pix: usize| is_transparent.is_some_and(|v| v[i_pix]);
+    if !dither {
+        let mut cache = std::collections::HashMap::<[u8; 3], u8>::new();
+        return Ok(img_data
+            .iter()
+            .enumerate()
+            .map(|(i_pix, c)| {
+                if is_skipped(i_pix) {
+                    return 0;
+                }
+                *cache
+                    .entry(*c)
+                    .or_insert_with(|| nearest(palette, &c.map(|v| v as f32)) as u8)
+            })
+            .collect());
+    }
+    let height = img_data.len() / width;
+    let mut err = vec![[0f32; 3]; img_data.len()];
+    let mut indices = vec![0u8; img_data.len()];
+    for iy in 0..height {
+        for ix in 0..width {
+            let i_pix = iy * width + ix;
+            if is_skipped(i_pix) {
+                continue;
+            }
+            let c: [f32; 3] = std::array::from_fn(|i| {
+                (img_data[i_pix][i] as f32 + err[i_pix][i]).clamp(0., 255.)
+            });
+            let i_color = nearest(palette, &c);
+            indices[i_pix] = i_color as u8;
+            let e: [f32; 3] = std::array::from_fn(|i| c[i] - palette[i_color][i] as f32);
+            let mut diffuse = |jx: usize, jy: usize, w: f32| {
+                if jx < width && jy < height && !is_skipped(jy * width + jx) {
+                    let e0 = &mut err[jy * width + jx];
+                    (0..3).for_each(|i| e0[i] += e[i] * w);
+                }
+            };
+            diffuse(ix + 1, iy, 7. / 16.);
+            if ix > 0 {
+                diffuse(ix - 1, iy + 1, 3. / 16.);
+            }
+            diffuse(ix, iy + 1, 5. / 16.);
+            diffuse(ix + 1, iy + 1, 1. / 16.);
+        }
+    }
+    Ok(indices)
+}
+
+#[test]
+fn test_quantize() -> anyhow::Result<()> {
+    // gradient from black to red
+    let width = 64;
+    let img: Vec<[u8; 3]> = (0..width * 8)
+        .map(|i| [((i % width) * 4) as u8, 0, 0])
+        .collect();
+    assert_eq!(median_cut(&img, 1), vec![[126, 0, 0]]);
+    let palette = median_cut(&img, 4);
+    assert_eq!(palette.len(), 4);
+    assert!(palette.iter().all(|c| c[1] == 0 && c[2] == 0));
+    // fewer colors than requested
+    assert_eq!(median_cut(&[[1, 2, 3], [1, 2, 3]], 16), vec![[1, 2, 3]]);
+    //
+    let palette = [[0, 0, 0], [255, 0, 0]];
+    let indices = palette_indices(&img, width, &palette, false, None)?;
+    assert_eq!(indices[10], 0);
+    assert_eq!(indices[50], 1);
+    // the mean of the dithered image approximates the original
+    let indices = palette_indices(&img, width, &palette, true, None)?;
+    let mean = |f: &dyn Fn(usize) -> f32| (0..img.len()).map(f).sum::<f32>() / img.len() as f32;
+    let mean_dithered = mean(&|i| palette[indices[i] as usize][0] as f32);
+    let mean_original = mean(&|i| img[i][0] as f32);
+    assert!((mean_dithered - mean_original).abs() < 4.);
+    // the error of the transparent pixel is not diffused to the next pixel
+    let img = [[120u8; 3], [100; 3]];
+    let indices = palette_indices(&img, 2, &[[0; 3], [255; 3]], true, None)?;
+    assert_eq!(indices, [0, 1]);
+    let indices = palette_indices(&img, 2, &[[0; 3], [255; 3]], true, Some(&[true, false]))?;
+    assert_eq!(indices, [0, 0]);
+    // the indices are `u8`
+    assert!(palette_indices(&img, 2, &[[0; 3]; 257], false, None).is_err());
+    assert!(palette_indices(&img, 2, &[], false, None).is_err());
+    Ok(())
+}
@@ -5,4 +5,5 @@ pub mod line2;
 pub mod polygon2;
 pub mod polyline2;
 pub mod triangle2;
+pub mod trimesh3;
 pub mod xy;
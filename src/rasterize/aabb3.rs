@@ -6,6 +6,27 @@ pub fn wireframe_dda<DATA>(
     val: DATA,
 ) where
     DATA: Copy,
+{
+    wireframe_dda_blend(
+        img_data,
+        img_shape,
+        transform_world2ndc,
+        aabb3,
+        val,
+        &crate::blend::Overwrite,
+    );
+}
+
+pub fn wireframe_dda_blend<DATA, BLEND>(
+    img_data: &mut [DATA],
+    img_shape: (usize, usize),
+    transform_world2ndc: &[f32; 16],
+    aabb3: &[f32; 6],
+    val: DATA,
+    blend: &BLEND,
+) where
+    DATA: Copy,
+    BLEND: crate::blend::Blend<DATA>,
 {
     let transform_ndc2pix = [
         0.5 * (img_shape.0 as f32),
@@ -40,12 +61,13 @@ pub fn wireframe_dda<DATA>(
         let q1 = transform_homogeneous(transform_world2ndc, &p1).unwrap();
         let r0 = mult_vec3(&transform_ndc2pix, &[q0[0], q0[1], 1f32]);
         let r1 = mult_vec3(&transform_ndc2pix, &[q1[0], q1[1], 1f32]);
-        crate::rasterize::line2::draw_dda_pixel_coordinate::<f32, DATA>(
+        crate::rasterize::line2::draw_dda_pixel_coordinate_blend::<f32, DATA, BLEND>(
             img_data,
             img_shape.0,
             &r0,
             &r1,
             val,
+            blend,
         );
     }
 }
@@ -43,12 +43,38 @@ pub fn fill<Real, VAL>(
     Real: num_traits::Float + 'static + AsPrimitive<i64>,
     i64: AsPrimitive<Real>,
     VAL: Copy,
+{
+    fill_blend(
+        img_data,
+        width,
+        x,
+        transform_world2pix,
+        rad_pix,
+        color,
+        &crate::blend::Overwrite,
+    );
+}
+
+/// * `transform` - 3x3 homogeneous transformation matrix with **column major** order
+pub fn fill_blend<Real, VAL, BLEND>(
+    img_data: &mut [VAL],
+    width: usize,
+    x: &[Real; 2],
+    transform_world2pix: &[Real; 9],
+    rad_pix: Real,
+    color: VAL,
+    blend: &BLEND,
+) where
+    Real: num_traits::Float + 'static + AsPrimitive<i64>,
+    i64: AsPrimitive<Real>,
+    VAL: Copy,
+    BLEND: crate::blend::Blend<VAL>,
 {
     let height = img_data.len() / width;
     let a = del_geo_core::mat3_col_major::transform_homogeneous(transform_world2pix, x).unwrap();
     let pixs = pixels_in_point(a[0], a[1], rad_pix, width, height);
     for idata in pixs {
-        img_data[idata] = color;
+        blend.blend(&mut img_data[idata], color, 1.0);
     }
 }
 
@@ -71,6 +97,39 @@ pub fn stroke_dda<Real, VAL>(
     usize: AsPrimitive<Real>,
     i32: AsPrimitive<Real>,
     VAL: Copy,
+{
+    stroke_dda_blend(
+        img_data,
+        width,
+        x,
+        rad,
+        transform,
+        color,
+        &crate::blend::Overwrite,
+    );
+}
+
+#[allow(clippy::identity_op)]
+pub fn stroke_dda_blend<Real, VAL, BLEND>(
+    img_data: &mut [VAL],
+    width: usize,
+    x: &[Real; 2],
+    rad: Real,
+    transform: &[Real; 9],
+    color: VAL,
+    blend: &BLEND,
+) where
+    Real: num_traits::Float
+        + num_traits::FloatConst
+        + 'static
+        + AsPrimitive<i64>
+        + AsPrimitive<usize>
+        + std::fmt::Debug,
+    i64: AsPrimitive<Real>,
+    usize: AsPrimitive<Real>,
+    i32: AsPrimitive<Real>,
+    VAL: Copy,
+    BLEND: crate::blend::Blend<VAL>,
 {
     let num_theta = 32;
     let two = Real::one() + Real::one();
@@ -88,6 +147,8 @@ pub fn stroke_dda<Real, VAL>(
         ];
         let q0 = del_geo_core::mat3_col_major::transform_homogeneous(transform, &p0).unwrap();
         let q1 = del_geo_core::mat3_col_major::transform_homogeneous(transform, &p1).unwrap();
-        crate::rasterize::line2::draw_dda_pixel_coordinate(img_data, width, &q0, &q1, color);
+        crate::rasterize::line2::draw_dda_pixel_coordinate_blend(
+            img_data, width, &q0, &q1, color, blend,
+        );
     }
 }
@@ -11,6 +11,22 @@ pub fn draw_dda_pixel_coordinate<Real, VAL>(
     Real: num_traits::Float + 'static + AsPrimitive<usize> + std::fmt::Debug,
     usize: AsPrimitive<Real>,
     VAL: Copy,
+{
+    draw_dda_pixel_coordinate_blend(img_data, width, p0, p1, i_color, &crate::blend::Overwrite);
+}
+
+pub fn draw_dda_pixel_coordinate_blend<Real, VAL, BLEND>(
+    img_data: &mut [VAL],
+    width: usize,
+    p0: &[Real; 2],
+    p1: &[Real; 2],
+    i_color: VAL,
+    blend: &BLEND,
+) where
+    Real: num_traits::Float + 'static + AsPrimitive<usize> + std::fmt::Debug,
+    usize: AsPrimitive<Real>,
+    VAL: Copy,
+    BLEND: crate::blend::Blend<VAL>,
 {
     let height = img_data.len() / width;
     let width_f: Real = width.as_();
@@ -31,7 +47,7 @@ pub fn draw_dda_pixel_coordinate<Real, VAL>(
         if x >= zero && x < width_f && y >= zero && y < height_f {
             let ix: usize = x.as_();
             let iy: usize = y.as_();
-            img_data[iy * width + ix] = i_color;
+            blend.blend(&mut img_data[iy * width + ix], i_color, 1.0);
         }
         x = x + slope_x;
         y = y + slope_y;
@@ -50,10 +66,36 @@ pub fn draw_dda<Real, VAL>(
     Real: num_traits::Float + std::fmt::Debug + 'static + AsPrimitive<usize>,
     usize: AsPrimitive<Real>,
     VAL: Copy,
+{
+    draw_dda_blend(
+        img_data,
+        width,
+        p0,
+        p1,
+        transform,
+        i_color,
+        &crate::blend::Overwrite,
+    );
+}
+
+/// * `transform` - 3x3 homogeneous transformation matrix with **column major** order
+pub fn draw_dda_blend<Real, VAL, BLEND>(
+    img_data: &mut [VAL],
+    width: usize,
+    p0: &[Real; 2],
+    p1: &[Real; 2],
+    transform: &[Real; 9],
+    i_color: VAL,
+    blend: &BLEND,
+) where
+    Real: num_traits::Float + std::fmt::Debug + 'static + AsPrimitive<usize>,
+    usize: AsPrimitive<Real>,
+    VAL: Copy,
+    BLEND: crate::blend::Blend<VAL>,
 {
     let q0 = del_geo_core::mat3_col_major::transform_homogeneous(transform, p0).unwrap();
     let q1 = del_geo_core::mat3_col_major::transform_homogeneous(transform, p1).unwrap();
-    draw_dda_pixel_coordinate(img_data, width, &q0, &q1, i_color);
+    draw_dda_pixel_coordinate_blend(img_data, width, &q0, &q1, i_color, blend);
 }
 
 pub fn pixels_in_line<Real>(
@@ -110,12 +152,164 @@ pub fn draw_pixcenter<T, VAL>(
     T: num_traits::Float + num_traits::AsPrimitive<usize>,
     usize: AsPrimitive<T>,
     VAL: Copy,
+{
+    draw_pixcenter_blend(
+        img_data,
+        width,
+        p0,
+        p1,
+        transform_world2pix,
+        thickness,
+        color,
+        &crate::blend::Overwrite,
+    );
+}
+
+/// * `transform` - 3x3 homogeneous transformation matrix with **column major** order
+#[allow(clippy::too_many_arguments)]
+pub fn draw_pixcenter_blend<T, VAL, BLEND>(
+    img_data: &mut [VAL],
+    width: usize,
+    p0: &[T; 2],
+    p1: &[T; 2],
+    transform_world2pix: &[T; 9],
+    thickness: T,
+    color: VAL,
+    blend: &BLEND,
+) where
+    T: num_traits::Float + num_traits::AsPrimitive<usize>,
+    usize: AsPrimitive<T>,
+    VAL: Copy,
+    BLEND: crate::blend::Blend<VAL>,
 {
     let height = img_data.len() / width;
     let a0 = del_geo_core::mat3_col_major::transform_homogeneous(transform_world2pix, p0).unwrap();
     let a1 = del_geo_core::mat3_col_major::transform_homogeneous(transform_world2pix, p1).unwrap();
     let pixs = pixels_in_line(a0[0], a0[1], a1[0], a1[1], thickness, width, height);
     for i_data in pixs {
-        img_data[i_data] = color;
+        blend.blend(&mut img_data[i_data], color, 1.0);
+    }
+}
+
+/// pixels overlapping a thick line segment together with their coverage in `[0, 1]`
+///
+/// The coverage is the integral of a one-pixel-wide box filter across the line
+/// evaluated at the pixel center, so lines thinner than a pixel fade out instead of breaking up.
+/// * `rad` - half of the line thickness in the pixel coordinate
+pub fn pixels_in_line_antialias<Real>(
+    x0: Real,
+    y0: Real,
+    x1: Real,
+    y1: Real,
+    rad: Real,
+    width: usize,
+    height: usize,
+) -> Vec<(usize, Real)>
+where
+    Real: num_traits::Float + 'static + AsPrimitive<usize>,
+    usize: AsPrimitive<Real>,
+{
+    let zero = Real::zero();
+    let one = Real::one();
+    let half: Real = one / (one + one);
+    let aabbi = {
+        let aabb = del_geo_core::aabb2::from_two_points(&[x0, y0], &[x1, y1], rad + one);
+        del_geo_core::aabb2::rasterize(&aabb, &(width, height))
+    };
+    let sqlen = (x1 - x0) * (x1 - x0) + (y1 - y0) * (y1 - y0);
+    let mut res = Vec::<(usize, Real)>::new();
+    for ih in aabbi[1]..aabbi[3] {
+        for iw in aabbi[0]..aabbi[2] {
+            let w: Real = iw.as_() + half; // pixel center
+            let h: Real = ih.as_() + half; // pixel center
+            let t = if sqlen == zero {
+                zero
+            } else {
+                ((w - x0) * (x1 - x0) + (h - y0) * (y1 - y0)) / sqlen
+            };
+            let t = t.max(zero).min(one);
+            let (xt, yt) = (x0 + (x1 - x0) * t, y0 + (y1 - y0) * t);
+            let dist = ((w - xt) * (w - xt) + (h - yt) * (h - yt)).sqrt();
+            // the part of the filter window lying on the far side of the line is subtracted
+            let coverage =
+                (rad + half - dist).max(zero).min(one) - (half - rad - dist).max(zero).min(one);
+            if coverage <= zero {
+                continue;
+            }
+            res.push((ih * width + iw, coverage));
+        }
+    }
+    res
+}
+
+/// draw anti-aliased line by blending the color with the pixel coverage
+/// (e.g., `BlendMode::AlphaOver` or `BlendMode::Add`)
+/// * `transform` - 3x3 homogeneous transformation matrix with **column major** order
+/// * `thickness` - half of the line thickness in the pixel coordinate (same as `draw_pixcenter`)
+#[allow(clippy::too_many_arguments)]
+pub fn draw_pixcenter_antialias<T, VAL, BLEND>(
+    img_data: &mut [VAL],
+    width: usize,
+    p0: &[T; 2],
+    p1: &[T; 2],
+    transform_world2pix: &[T; 9],
+    thickness: T,
+    color: VAL,
+    blend: &BLEND,
+) where
+    T: num_traits::Float + num_traits::AsPrimitive<usize> + AsPrimitive<f32>,
+    usize: AsPrimitive<T>,
+    VAL: Copy,
+    BLEND: crate::blend::Blend<VAL>,
+{
+    let height = img_data.len() / width;
+    let a0 = del_geo_core::mat3_col_major::transform_homogeneous(transform_world2pix, p0).unwrap();
+    let a1 = del_geo_core::mat3_col_major::transform_homogeneous(transform_world2pix, p1).unwrap();
+    let pixs = pixels_in_line_antialias(a0[0], a0[1], a1[0], a1[1], thickness, width, height);
+    for (i_pix, coverage) in pixs {
+        blend.blend(&mut img_data[i_pix], color, coverage.as_());
+    }
+}
+
+#[test]
+fn test_antialias() -> anyhow::Result<()> {
+    let img_size = (100usize, 100usize);
+    let trans_world2pix =
+        crate::cam2::transform_world2pix_ortho_preserve_asp(&img_size, &[-0.1, -0.1, 1.1, 1.1]);
+    let mut img_data = vec![1f32; img_size.0 * img_size.1 * 3];
+    for (i_line, &thickness) in [0.3f32, 0.5, 1.0, 2.5].iter().enumerate() {
+        let y = 0.1 + 0.25 * i_line as f32;
+        use slice_of_array::SliceNestExt;
+        draw_pixcenter_antialias(
+            img_data.nest_mut(),
+            img_size.0,
+            &[0.0, y],
+            &[1.0, y + 0.15],
+            &trans_world2pix,
+            thickness,
+            [0.0, 0.0, 1.0],
+            &crate::blend::BlendMode::AlphaOver,
+        );
+    }
+    // a horizontal line with the thickness of exactly one pixel is fully opaque only on its axis
+    let pixs = pixels_in_line_antialias(10.0f32, 50.5, 90.0, 50.5, 0.5, 100, 100);
+    for &(i_pix, coverage) in pixs.iter() {
+        let (iw, ih) = (i_pix % 100, i_pix / 100);
+        if (11..89).contains(&iw) {
+            assert_eq!(ih, 50);
+            assert!((coverage - 1.0).abs() < 1.0e-5);
+        }
     }
+    // the total coverage approximates the area of the thin line
+    let pixs = pixels_in_line_antialias(10.0f32, 20.0, 80.0, 70.0, 0.2, 100, 100);
+    let area: f32 = pixs.iter().map(|v| v.1).sum();
+    let length = (70f32 * 70. + 50. * 50.).sqrt();
+    assert!((area - length * 0.4).abs() < length * 0.4 * 0.05);
+    crate::write_png_from_float_image(
+        "target/rasterize_line2-test_antialias.png",
+        img_size,
+        3,
+        &img_data,
+    )?;
+    Ok(())
 }
@@ -14,11 +14,38 @@ pub fn stroke<T, VAL>(
     T: num_traits::Float + num_traits::AsPrimitive<usize>,
     usize: AsPrimitive<T>,
     VAL: Copy,
+{
+    stroke_blend(
+        img_data,
+        width,
+        vtx2xy,
+        transform_xy2pix,
+        thickness,
+        color,
+        &crate::blend::Overwrite,
+    );
+}
+
+/// * `transform` - 3x3 homogeneous transformation matrix with **column major** order
+#[allow(clippy::identity_op)]
+pub fn stroke_blend<T, VAL, BLEND>(
+    img_data: &mut [VAL],
+    width: usize,
+    vtx2xy: &[T],
+    transform_xy2pix: &[T; 9],
+    thickness: T,
+    color: VAL,
+    blend: &BLEND,
+) where
+    T: num_traits::Float + num_traits::AsPrimitive<usize>,
+    usize: AsPrimitive<T>,
+    VAL: Copy,
+    BLEND: crate::blend::Blend<VAL>,
 {
     let n = vtx2xy.len() / 2;
     for i in 0..n {
         let j = (i + 1) % n;
-        crate::rasterize::line2::draw_pixcenter(
+        crate::rasterize::line2::draw_pixcenter_blend(
             img_data,
             width,
             &[vtx2xy[i * 2], vtx2xy[i * 2 + 1]],
@@ -26,17 +53,54 @@ pub fn stroke<T, VAL>(
             transform_xy2pix,
             thickness,
             color,
+            blend,
         );
     }
 }
 
-#[allow(clippy::identity_op)]
+/// fill the pixels whose centers have the winding number of one.
+/// This calls `fill_scanline`.
+/// * `transform` - 3x3 homogeneous transformation matrix with **column major** order
 pub fn fill<T, VAL>(
     img_data: &mut [VAL],
     width: usize,
     vtx2xy: &[T],
     transform_xy2pix: &[T; 9],
     color: VAL,
+) where
+    T: num_traits::Float + num_traits::AsPrimitive<usize>,
+    usize: AsPrimitive<T>,
+    VAL: Copy,
+{
+    fill_scanline(img_data, width, vtx2xy, transform_xy2pix, color);
+}
+
+/// * `transform` - 3x3 homogeneous transformation matrix with **column major** order
+pub fn fill_blend<T, VAL, BLEND>(
+    img_data: &mut [VAL],
+    width: usize,
+    vtx2xy: &[T],
+    transform_xy2pix: &[T; 9],
+    color: VAL,
+    blend: &BLEND,
+) where
+    T: num_traits::Float + num_traits::AsPrimitive<usize>,
+    usize: AsPrimitive<T>,
+    VAL: Copy,
+    BLEND: crate::blend::Blend<VAL>,
+{
+    fill_scanline_blend(img_data, width, vtx2xy, transform_xy2pix, color, blend);
+}
+
+/// reference implementation evaluating the winding number at every pixel in the bounding box.
+/// This is slow for the polygon with many vertices. Use `fill` instead
+#[allow(clippy::identity_op)]
+pub fn fill_per_pixel<T, VAL>(
+    img_data: &mut [VAL],
+    width: usize,
+    vtx2xy: &[T],
+    transform_xy2pix: &[T; 9],
+    color: VAL,
 ) where
     T: num_traits::Float
         + num_traits::FloatConst
@@ -68,6 +132,245 @@ pub fn fill<T, VAL>(
     }
 }
 
+/// fill polygon with the scanline algorithm. The pixels filled are the same as `fill_per_pixel`
+/// (i.e., the pixel centers where the winding number is one), but the cost does not grow
+/// with the product of the number of vertices and the number of pixels.
+/// * `transform` - 3x3 homogeneous transformation matrix with **column major** order
+pub fn fill_scanline<T, VAL>(
+    img_data: &mut [VAL],
+    width: usize,
+    vtx2xy: &[T],
+    transform_xy2pix: &[T; 9],
+    color: VAL,
+) where
+    T: num_traits::Float + num_traits::AsPrimitive<usize>,
+    usize: AsPrimitive<T>,
+    VAL: Copy,
+{
+    fill_scanline_blend(
+        img_data,
+        width,
+        vtx2xy,
+        transform_xy2pix,
+        color,
+        &crate::blend::Overwrite,
+    );
+}
+
+/// * `transform` - 3x3 homogeneous transformation matrix with **column major** order
+pub fn fill_scanline_blend<T, VAL, BLEND>(
+    img_data: &mut [VAL],
+    width: usize,
+    vtx2xy: &[T],
+    transform_xy2pix: &[T; 9],
+    color: VAL,
+    blend: &BLEND,
+) where
+    T: num_traits::Float + num_traits::AsPrimitive<usize>,
+    usize: AsPrimitive<T>,
+    VAL: Copy,
+    BLEND: crate::blend::Blend<VAL>,
+{
+    let height = img_data.len() / width;
+    let vtx2pix = transform_vtx2xy(vtx2xy, transform_xy2pix);
+    let sign = orientation_sign(transform_xy2pix);
+    scanline_spans(&[vtx2pix], (width, height), &mut |i_h, range, wn| {
+        if wn * sign != 1 {
+            return;
+        }
+        for i_w in range {
+            blend.blend(&mut img_data[i_h * width + i_w], color, 1.0);
+        }
+    });
+}
+
+/// rule to decide the inside of the shape from the winding number (same as the SVG `fill-rule`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FillRule {
+    /// the winding number is not zero
+    NonZero,
+    /// the winding number is odd
+    EvenOdd,
+    /// the winding number is positive (i.e., enclosed by counter-clockwise loops)
+    Positive,
+    /// the winding number is negative (i.e., enclosed by clockwise loops)
+    Negative,
+}
+
+impl FillRule {
+    pub fn is_inside(&self, winding_number: i32) -> bool {
+        match self {
+            FillRule::NonZero => winding_number != 0,
+            FillRule::EvenOdd => winding_number % 2 != 0,
+            FillRule::Positive => winding_number > 0,
+            FillRule::Negative => winding_number < 0,
+        }
+    }
+}
+
+/// fill the shape made of several polygon loops (e.g., glyph with holes)
+/// * `loops` - each loop is stored as `[x0, y0, x1, y1, ...]`
+/// * `transform` - 3x3 homogeneous transformation matrix with **column major** order
+/// * `fill_rule` - the winding number is counted counter-clockwise in the `xy` coordinate
+pub fn fill_loops<T, LOOP, VAL>(
+    img_data: &mut [VAL],
+    width: usize,
+    loops: &[LOOP],
+    transform_xy2pix: &[T; 9],
+    fill_rule: FillRule,
+    color: VAL,
+) where
+    T: num_traits::Float + num_traits::AsPrimitive<usize>,
+    usize: AsPrimitive<T>,
+    LOOP: AsRef<[T]>,
+    VAL: Copy,
+{
+    fill_loops_blend(
+        img_data,
+        width,
+        loops,
+        transform_xy2pix,
+        fill_rule,
+        color,
+        &crate::blend::Overwrite,
+    );
+}
+
+/// * `transform` - 3x3 homogeneous transformation matrix with **column major** order
+pub fn fill_loops_blend<T, LOOP, VAL, BLEND>(
+    img_data: &mut [VAL],
+    width: usize,
+    loops: &[LOOP],
+    transform_xy2pix: &[T; 9],
+    fill_rule: FillRule,
+    color: VAL,
+    blend: &BLEND,
+) where
+    T: num_traits::Float + num_traits::AsPrimitive<usize>,
+    usize: AsPrimitive<T>,
+    LOOP: AsRef<[T]>,
+    VAL: Copy,
+    BLEND: crate::blend::Blend<VAL>,
+{
+    let height = img_data.len() / width;
+    let loops_pix: Vec<Vec<T>> = loops
+        .iter()
+        .map(|vtx2xy| transform_vtx2xy(vtx2xy.as_ref(), transform_xy2pix))
+        .collect();
+    let sign = orientation_sign(transform_xy2pix);
+    scanline_spans(&loops_pix, (width, height), &mut |i_h, range, wn| {
+        if !fill_rule.is_inside(wn * sign) {
+            return;
+        }
+        for i_w in range {
+            blend.blend(&mut img_data[i_h * width + i_w], color, 1.0);
+        }
+    });
+}
+
+/// visit the spans of the pixel centers having the same nonzero winding number
+/// using the scanline algorithm with the active edge table.
+/// * `loops_pix` - polygon loops in the pixel coordinate, each stored as `[x0, y0, x1, y1, ...]`
+/// * `f` - called as `f(i_h, i_w_start..i_w_end, winding_number)`. The winding number is positive
+///   for loops with the positive signed area in the pixel coordinate (clockwise on screen)
+#[allow(clippy::type_complexity)]
+pub fn scanline_spans<T, LOOP, F>(loops_pix: &[LOOP], img_shape: (usize, usize), f: &mut F)
+where
+    T: num_traits::Float + num_traits::AsPrimitive<usize>,
+    usize: AsPrimitive<T>,
+    LOOP: AsRef<[T]>,
+    F: FnMut(usize, std::ops::Range<usize>, i32),
+{
+    let zero = T::zero();
+    let half = T::one() / (T::one() + T::one());
+    // (y_min, y_max, start point, end point, direction)
+    let mut edges: Vec<(T, T, [T; 2], [T; 2], i32)> = vec![];
+    for vtx2xy in loops_pix {
+        let vtx2xy = vtx2xy.as_ref();
+        let num_vtx = vtx2xy.len() / 2;
+        for i_vtx in 0..num_vtx {
+            let j_vtx = (i_vtx + 1) % num_vtx;
+            let pi = [vtx2xy[i_vtx * 2], vtx2xy[i_vtx * 2 + 1]];
+            let pj = [vtx2xy[j_vtx * 2], vtx2xy[j_vtx * 2 + 1]];
+            // skip the horizontal edges and the edges with NaN or infinite coordinates
+            if pi[1] == pj[1] || !pi.iter().chain(pj.iter()).all(|v| v.is_finite()) {
+                continue;
+            }
+            if pi[1] < pj[1] {
+                edges.push((pi[1], pj[1], pi, pj, 1));
+            } else {
+                edges.push((pj[1], pi[1], pi, pj, -1));
+            }
+        }
+    }
+    edges.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(std::cmp::Ordering::Equal));
+    let width_f: T = img_shape.0.as_();
+    let to_iw = |x: T| -> usize { (x - half).ceil().max(zero).min(width_f).as_() };
+    let mut i_edge_next = 0;
+    let mut active: Vec<usize> = vec![];
+    let mut crossings: Vec<(T, i32)> = vec![];
+    for i_h in 0..img_shape.1 {
+        let y: T = i_h.as_() + half; // pixel center
+        while i_edge_next < edges.len() && edges[i_edge_next].0 <= y {
+            active.push(i_edge_next);
+            i_edge_next += 1;
+        }
+        // an edge covers `y_min <= y < y_max` so that a vertex is counted only once
+        active.retain(|&i_edge| edges[i_edge].1 > y);
+        if active.is_empty() {
+            if i_edge_next == edges.len() {
+                break;
+            }
+            continue;
+        }
+        crossings.clear();
+        for &i_edge in active.iter() {
+            let (_, _, ps, pe, dir) = edges[i_edge];
+            let x = ps[0] + (pe[0] - ps[0]) * (y - ps[1]) / (pe[1] - ps[1]);
+            crossings.push((x, dir));
+        }
+        crossings.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(std::cmp::Ordering::Equal));
+        // the ray toward +x from the pixel center crosses all the edges on its right
+        let mut wn: i32 = crossings.iter().map(|c| c.1).sum();
+        for i_cross in 0..crossings.len() - 1 {
+            wn -= crossings[i_cross].1;
+            if wn == 0 {
+                continue;
+            }
+            let iw0 = to_iw(crossings[i_cross].0);
+            let iw1 = to_iw(crossings[i_cross + 1].0);
+            if iw0 < iw1 {
+                f(i_h, iw0..iw1, wn);
+            }
+        }
+    }
+}
+
+/// transform the vertices of the polygon
+pub fn transform_vtx2xy<T>(vtx2xy: &[T], transform: &[T; 9]) -> Vec<T>
+where
+    T: num_traits::Float,
+{
+    vtx2xy
+        .chunks(2)
+        .flat_map(|v| {
+            del_geo_core::mat3_col_major::transform_homogeneous(transform, &[v[0], v[1]]).unwrap()
+        })
+        .collect()
+}
+
+/// `-1` if the transformation flips the orientation (e.g., y-axis pointing down in the pixel coordinate)
+fn orientation_sign<T>(transform: &[T; 9]) -> i32
+where
+    T: num_traits::Float,
+{
+    if del_geo_core::mat3_col_major::determinant(transform) < T::zero() {
+        -1
+    } else {
+        1
+    }
+}
+
 #[test]
 fn test0() -> anyhow::Result<()> {
     let img_size = (100usize, 100usize);
@@ -123,3 +426,73 @@ where
     }
     wn
 }
+
+#[test]
+fn test_scanline() {
+    let img_size = (123usize, 97usize);
+    let trans_world2pix =
+        crate::cam2::transform_world2pix_ortho_preserve_asp(&img_size, &[-0.1, -0.1, 1.1, 1.1]);
+    let num_vtx = 37;
+    let vtx2xy: Vec<f32> = (0..num_vtx)
+        .flat_map(|i_vtx| {
+            let theta = 2. * std::f32::consts::PI * i_vtx as f32 / num_vtx as f32;
+            let r = if i_vtx % 2 == 0 {
+                0.55
+            } else {
+                0.2 + 0.01 * i_vtx as f32
+            };
+            [0.5 + r * theta.cos(), 0.5 + r * theta.sin()]
+        })
+        .collect();
+    let vtx2xy_rev: Vec<f32> = vtx2xy.chunks(2).rev().flatten().copied().collect();
+    // the clockwise polygon has the winding number of -1 so it is not filled
+    for (vtx2xy, is_filled) in [(vtx2xy, true), (vtx2xy_rev, false)] {
+        let mut img0 = vec![0u8; img_size.0 * img_size.1];
+        fill_per_pixel(&mut img0, img_size.0, &vtx2xy, &trans_world2pix, 1u8);
+        let mut img1 = vec![0u8; img_size.0 * img_size.1];
+        fill_scanline(&mut img1, img_size.0, &vtx2xy, &trans_world2pix, 1u8);
+        assert_eq!(img0, img1);
+        assert_eq!(img0.contains(&1), is_filled);
+    }
+    // the edges at a NaN vertex are skipped instead of panicking
+    let mut img = vec![0u8; img_size.0 * img_size.1];
+    let vtx2xy = [0.1f32, 0.1, 0.9, 0.1, f32::NAN, 0.5, 0.9, 0.9, 0.1, 0.9];
+    fill(&mut img, img_size.0, &vtx2xy, &trans_world2pix, 1u8);
+}
+
+#[test]
+fn test_fill_rule() {
+    let img_size = (40usize, 40usize);
+    let trans_world2pix =
+        crate::cam2::transform_world2pix_ortho_preserve_asp(&img_size, &[0., 0., 1., 1.]);
+    let outer = [0.1f32, 0.1, 0.9, 0.1, 0.9, 0.9, 0.1, 0.9];
+    let inner_ccw = [0.3f32, 0.3, 0.7, 0.3, 0.7, 0.7, 0.3, 0.7];
+    let inner_cw = [0.3f32, 0.3, 0.3, 0.7, 0.7, 0.7, 0.7, 0.3];
+    let at = |img: &[u8], x: f32, y: f32| {
+        let p =
+            del_geo_core::mat3_col_major::transform_homogeneous(&trans_world2pix, &[x, y]).unwrap();
+        img[(p[1] as usize) * img_size.0 + (p[0] as usize)]
+    };
+    for (inner, fill_rule, is_center_filled) in [
+        (&inner_cw, FillRule::NonZero, false),
+        (&inner_cw, FillRule::EvenOdd, false),
+        (&inner_ccw, FillRule::NonZero, true),
+        (&inner_ccw, FillRule::EvenOdd, false),
+        (&inner_ccw, FillRule::Positive, true),
+        (&inner_ccw, FillRule::Negative, false),
+    ] {
+        let mut img = vec![0u8; img_size.0 * img_size.1];
+        let loops = [&outer, inner];
+        fill_loops(
+            &mut img,
+            img_size.0,
+            &loops,
+            &trans_world2pix,
+            fill_rule,
+            1u8,
+        );
+        assert_eq!(at(&img, 0.5, 0.5) == 1, is_center_filled, "{:?}", fill_rule);
+        assert_eq!(at(&img, 0.05, 0.5), 0);
+        assert_eq!(at(&img, 0.2, 0.5) == 1, fill_rule != FillRule::Negative);
+    }
+}
@@ -12,10 +12,43 @@ pub fn stroke_dda<T, VAL>(
     T: num_traits::Float + num_traits::AsPrimitive<usize> + std::fmt::Debug,
     usize: AsPrimitive<T>,
     VAL: Copy,
+{
+    stroke_dda_blend(
+        img_data,
+        width,
+        vtx2xy,
+        transform_xy2pix,
+        color,
+        &crate::blend::Overwrite,
+    );
+}
+
+/// # Argument
+/// * `transform` - 3x3 homogeneous transformation matrix with **column major** order
+pub fn stroke_dda_blend<T, VAL, BLEND>(
+    img_data: &mut [VAL],
+    width: usize,
+    vtx2xy: &[[T; 2]],
+    transform_xy2pix: &[T; 9],
+    color: VAL,
+    blend: &BLEND,
+) where
+    T: num_traits::Float + num_traits::AsPrimitive<usize> + std::fmt::Debug,
+    usize: AsPrimitive<T>,
+    VAL: Copy,
+    BLEND: crate::blend::Blend<VAL>,
 {
     for vtx in vtx2xy.windows(2) {
         let p0 = &vtx[0];
         let p1 = &vtx[1];
-        crate::rasterize::line2::draw_dda(img_data, width, p0, p1, transform_xy2pix, color);
+        crate::rasterize::line2::draw_dda_blend(
+            img_data,
+            width,
+            p0,
+            p1,
+            transform_xy2pix,
+            color,
+            blend,
+        );
     }
 }
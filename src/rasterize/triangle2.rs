@@ -1,6 +1,7 @@
 use num_traits::AsPrimitive;
 
-#[allow(clippy::identity_op)]
+/// fill the pixels whose centers are inside the triangle.
+/// This calls `fill_incremental`.
 pub fn fill<Index, Real, VAL>(
     pix2color: &mut [VAL],
     img_width: usize,
@@ -14,6 +15,54 @@ pub fn fill<Index, Real, VAL>(
     Index: AsPrimitive<usize>,
     usize: AsPrimitive<Real>,
     VAL: Copy,
+{
+    fill_incremental(pix2color, img_width, p0, p1, p2, transform_xy2pix, i_color);
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn fill_blend<Index, Real, VAL, BLEND>(
+    pix2color: &mut [VAL],
+    img_width: usize,
+    p0: &[Real; 2],
+    p1: &[Real; 2],
+    p2: &[Real; 2],
+    transform_xy2pix: &[Real; 9],
+    i_color: VAL,
+    blend: &BLEND,
+) where
+    Real: num_traits::Float + 'static + Copy + AsPrimitive<usize>,
+    Index: AsPrimitive<usize>,
+    usize: AsPrimitive<Real>,
+    VAL: Copy,
+    BLEND: crate::blend::Blend<VAL>,
+{
+    fill_incremental_blend(
+        pix2color,
+        img_width,
+        p0,
+        p1,
+        p2,
+        transform_xy2pix,
+        i_color,
+        blend,
+    );
+}
+
+/// reference implementation testing every pixel in the bounding box with `tri2::is_inside`.
+/// Use `fill` instead
+#[allow(clippy::identity_op)]
+pub fn fill_per_pixel<Real, VAL>(
+    pix2color: &mut [VAL],
+    img_width: usize,
+    p0: &[Real; 2],
+    p1: &[Real; 2],
+    p2: &[Real; 2],
+    transform_xy2pix: &[Real; 9],
+    i_color: VAL,
+) where
+    Real: num_traits::Float + 'static + Copy + AsPrimitive<usize>,
+    usize: AsPrimitive<Real>,
+    VAL: Copy,
 {
     let half = Real::one() / (Real::one() + Real::one());
     let img_height = pix2color.len() / img_width;
@@ -38,6 +87,155 @@ pub fn fill<Index, Real, VAL>(
     }
 }
 
+/// fill triangle by evaluating the edge functions incrementally along the rows.
+/// The pixels filled are the same as `fill_per_pixel`.
+pub fn fill_incremental<Real, VAL>(
+    pix2color: &mut [VAL],
+    img_width: usize,
+    p0: &[Real; 2],
+    p1: &[Real; 2],
+    p2: &[Real; 2],
+    transform_xy2pix: &[Real; 9],
+    i_color: VAL,
+) where
+    Real: num_traits::Float + 'static + Copy + AsPrimitive<usize>,
+    usize: AsPrimitive<Real>,
+    VAL: Copy,
+{
+    fill_incremental_blend(
+        pix2color,
+        img_width,
+        p0,
+        p1,
+        p2,
+        transform_xy2pix,
+        i_color,
+        &crate::blend::Overwrite,
+    );
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn fill_incremental_blend<Real, VAL, BLEND>(
+    pix2color: &mut [VAL],
+    img_width: usize,
+    p0: &[Real; 2],
+    p1: &[Real; 2],
+    p2: &[Real; 2],
+    transform_xy2pix: &[Real; 9],
+    i_color: VAL,
+    blend: &BLEND,
+) where
+    Real: num_traits::Float + 'static + Copy + AsPrimitive<usize>,
+    usize: AsPrimitive<Real>,
+    VAL: Copy,
+    BLEND: crate::blend::Blend<VAL>,
+{
+    let zero = Real::zero();
+    let half = Real::one() / (Real::one() + Real::one());
+    let img_height = pix2color.len() / img_width;
+    use del_geo_core::mat3_col_major::transform_homogeneous;
+    let q0: [Real; 2] = transform_homogeneous(transform_xy2pix, p0).unwrap();
+    let q1: [Real; 2] = transform_homogeneous(transform_xy2pix, p1).unwrap();
+    let q2: [Real; 2] = transform_homogeneous(transform_xy2pix, p2).unwrap();
+    let aabbi = {
+        let aabb = crate::rasterize::polygon2::aabb2(&[q0[0], q0[1], q1[0], q1[1], q2[0], q2[1]]);
+        del_geo_core::aabb2::rasterize(&aabb, &(img_width, img_height))
+    };
+    // `edge_function(q) = a * q.x + b * q.y + c` is the signed area `tri2::area(q, qa, qb)`
+    // negated as the sign `-1` used in `fill_per_pixel`
+    let edge_function = |qa: &[Real; 2], qb: &[Real; 2]| {
+        [
+            -half * (qa[1] - qb[1]),
+            -half * (qb[0] - qa[0]),
+            -half * (qa[0] * qb[1] - qb[0] * qa[1]),
+        ]
+    };
+    let edges = [
+        edge_function(&q1, &q2),
+        edge_function(&q2, &q0),
+        edge_function(&q0, &q1),
+    ];
+    let x0: Real = aabbi[0].as_() + half;
+    for i_h in aabbi[1]..aabbi[3] {
+        let y: Real = i_h.as_() + half;
+        let mut e = edges.map(|abc| abc[0] * x0 + abc[1] * y + abc[2]);
+        let mut is_inside_prev = false;
+        for i_w in aabbi[0]..aabbi[2] {
+            let is_inside = e[0] >= zero && e[1] >= zero && e[2] >= zero;
+            if is_inside {
+                blend.blend(&mut pix2color[i_h * img_width + i_w], i_color, 1.0);
+            } else if is_inside_prev {
+                break; // the triangle is convex
+            }
+            is_inside_prev = is_inside;
+            for i_edge in 0..3 {
+                e[i_edge] = e[i_edge] + edges[i_edge][0];
+            }
+        }
+    }
+}
+
+/// fill triangle while interpolating per-vertex attributes (e.g., color, uv, scalar)
+/// * `vtx2attr` - attributes at the three vertices
+/// * `vtx2w` - homogeneous coordinate `w` of the three vertices for the perspective-correct interpolation.
+///   If `None`, the attributes are interpolated linearly in the pixel coordinate
+/// * `shader` - called as `shader(&mut pix2color[i_pix], &attr)` for the pixel centers inside the triangle
+#[allow(clippy::too_many_arguments)]
+pub fn fill_interpolate<Real, VAL, const N: usize, SHADER>(
+    pix2color: &mut [VAL],
+    img_width: usize,
+    p0: &[Real; 2],
+    p1: &[Real; 2],
+    p2: &[Real; 2],
+    transform_xy2pix: &[Real; 9],
+    vtx2attr: &[[Real; N]; 3],
+    vtx2w: Option<&[Real; 3]>,
+    shader: &mut SHADER,
+) where
+    Real: num_traits::Float + 'static + Copy + AsPrimitive<usize>,
+    usize: AsPrimitive<Real>,
+    SHADER: FnMut(&mut VAL, &[Real; N]),
+{
+    let half = Real::one() / (Real::one() + Real::one());
+    let img_height = pix2color.len() / img_width;
+    use del_geo_core::mat3_col_major::transform_homogeneous;
+    let q0: [Real; 2] = transform_homogeneous(transform_xy2pix, p0).unwrap();
+    let q1: [Real; 2] = transform_homogeneous(transform_xy2pix, p1).unwrap();
+    let q2: [Real; 2] = transform_homogeneous(transform_xy2pix, p2).unwrap();
+    let aabbi = {
+        let aabb = crate::rasterize::polygon2::aabb2(&[q0[0], q0[1], q1[0], q1[1], q2[0], q2[1]]);
+        del_geo_core::aabb2::rasterize(&aabb, &(img_width, img_height))
+    };
+    for i_h in aabbi[1]..aabbi[3] {
+        for i_w in aabbi[0]..aabbi[2] {
+            let p_xy: [Real; 2] = [i_w.as_() + half, i_h.as_() + half];
+            let Some((r0, r1)) = del_geo_core::tri2::is_inside(&q0, &q1, &q2, &p_xy, -Real::one())
+            else {
+                continue;
+            };
+            let bc = barycentric_perspective_correct(&[r0, r1, Real::one() - r0 - r1], vtx2w);
+            let attr: [Real; N] = std::array::from_fn(|i_dim| {
+                bc[0] * vtx2attr[0][i_dim] + bc[1] * vtx2attr[1][i_dim] + bc[2] * vtx2attr[2][i_dim]
+            });
+            shader(&mut pix2color[i_h * img_width + i_w], &attr);
+        }
+    }
+}
+
+/// convert the barycentric coordinates in the screen space into those in the space before the projection
+/// * `vtx2w` - homogeneous coordinate `w` of the three vertices. Return `bc` as is if `None`
+pub fn barycentric_perspective_correct<Real>(bc: &[Real; 3], vtx2w: Option<&[Real; 3]>) -> [Real; 3]
+where
+    Real: num_traits::Float,
+{
+    let Some(vtx2w) = vtx2w else {
+        return *bc;
+    };
+    let a = [bc[0] / vtx2w[0], bc[1] / vtx2w[1], bc[2] / vtx2w[2]];
+    let sum_inv = Real::one() / (a[0] + a[1] + a[2]);
+    [a[0] * sum_inv, a[1] * sum_inv, a[2] * sum_inv]
+}
+
 #[test]
 fn test0() -> anyhow::Result<()> {
     let img_size = (100usize, 100usize);
@@ -61,3 +259,79 @@ fn test0() -> anyhow::Result<()> {
     )?;
     Ok(())
 }
+
+#[test]
+fn test_interpolate() -> anyhow::Result<()> {
+    let img_size = (100usize, 100usize);
+    let trans_xy2pix =
+        crate::cam2::transform_world2pix_ortho_preserve_asp(&img_size, &[-0.1, -0.1, 1.1, 1.1]);
+    let mut img_data = vec![[0f32; 3]; img_size.0 * img_size.1];
+    let vtx2rgb = [[1f32, 0., 0.], [0., 1., 0.], [0., 0., 1.]];
+    let (p0, p1, p2) = ([0.1, 0.1], [0.9, 0.1], [0.5, 0.9]);
+    fill_interpolate(
+        &mut img_data,
+        img_size.0,
+        &p0,
+        &p1,
+        &p2,
+        &trans_xy2pix,
+        &vtx2rgb,
+        None,
+        &mut |pix: &mut [f32; 3], rgb: &[f32; 3]| *pix = *rgb,
+    );
+    // the color at the centroid is the average of the vertex colors
+    {
+        let c = del_geo_core::mat3_col_major::transform_homogeneous(
+            &trans_xy2pix,
+            &[(p0[0] + p1[0] + p2[0]) / 3., (p0[1] + p1[1] + p2[1]) / 3.],
+        )
+        .unwrap();
+        let rgb = img_data[(c[1] as usize) * img_size.0 + (c[0] as usize)];
+        rgb.iter()
+            .for_each(|&v| assert!((v - 1. / 3.).abs() < 0.05));
+    }
+    // the perspective correction is identity for the uniform `w`
+    assert_eq!(
+        barycentric_perspective_correct(&[0.2f32, 0.3, 0.5], Some(&[2., 2., 2.])),
+        [0.2, 0.3, 0.5]
+    );
+    // a vertex far from the camera occupies a smaller portion of the screen-space triangle
+    let bc = barycentric_perspective_correct(&[0.5f32, 0.5, 0.0], Some(&[1., 3., 1.]));
+    assert!((bc[0] - 0.75).abs() < 1.0e-6 && (bc[1] - 0.25).abs() < 1.0e-6);
+    use slice_of_array::SliceFlatExt;
+    crate::write_png_from_float_image(
+        "target/rasterize_triangle-test_interpolate.png",
+        img_size,
+        3,
+        img_data.flat(),
+    )?;
+    Ok(())
+}
+
+#[test]
+fn test_incremental() {
+    let img_size = (64usize, 48usize);
+    let trans_xy2pix =
+        crate::cam2::transform_world2pix_ortho_preserve_asp(&img_size, &[0., 0., 1., 1.]);
+    // linear congruential generator for the reproducible random coordinates
+    let mut seed = 12345u64;
+    let mut rand = || {
+        seed = seed
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        ((seed >> 33) as f32) / ((1u64 << 31) as f32) * 1.4 - 0.2
+    };
+    let mut num_filled = 0;
+    for _itr in 0..200 {
+        let p0 = [rand(), rand()];
+        let p1 = [rand(), rand()];
+        let p2 = [rand(), rand()];
+        let mut img0 = vec![0u8; img_size.0 * img_size.1];
+        fill_per_pixel(&mut img0, img_size.0, &p0, &p1, &p2, &trans_xy2pix, 1);
+        let mut img1 = vec![0u8; img_size.0 * img_size.1];
+        fill_incremental(&mut img1, img_size.0, &p0, &p1, &p2, &trans_xy2pix, 1);
+        assert_eq!(img0, img1);
+        num_filled += img0.iter().filter(|&&v| v == 1).count();
+    }
+    assert!(num_filled > 0);
+}
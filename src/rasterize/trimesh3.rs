@@ -0,0 +1,348 @@
+//! rasterization of 3D triangle mesh with the depth buffer.
+//!
+//! The normalized device coordinate (NDC) follows `cam3::ray3_homogeneous`:
+//! the near plane is at `z=+1`, the far plane is at `z=-1`, and the triangles
+//! counter-clockwise in the NDC are front facing.
+
+/// triangle clipped by the near plane (`z <= w` in the clip coordinate)
+/// and the barycentric coordinates of its corners w.r.t. the original triangle.
+fn clip_near_plane(vtx2clip: &[[f32; 4]; 3]) -> Vec<([f32; 4], [f32; 3])> {
+    let bcs = [[1f32, 0., 0.], [0., 1., 0.], [0., 0., 1.]];
+    let dist = |c: &[f32; 4]| c[3] - c[2];
+    let mut res = Vec::<([f32; 4], [f32; 3])>::with_capacity(4);
+    for i_node in 0..3 {
+        let j_node = (i_node + 1) % 3;
+        let (ci, cj) = (&vtx2clip[i_node], &vtx2clip[j_node]);
+        let (di, dj) = (dist(ci), dist(cj));
+        if di >= 0. {
+            res.push((*ci, bcs[i_node]));
+        }
+        if (di >= 0.) != (dj >= 0.) {
+            let r = di / (di - dj);
+            let c: [f32; 4] = std::array::from_fn(|k| ci[k] + (cj[k] - ci[k]) * r);
+            let bc: [f32; 3] =
+                std::array::from_fn(|k| bcs[i_node][k] + (bcs[j_node][k] - bcs[i_node][k]) * r);
+            res.push((c, bc));
+        }
+    }
+    res
+}
+
+/// rasterize triangle mesh with the depth test.
+/// The pixel centers are sampled (i.e., `(i_w + 0.5, i_h + 0.5)` in the pixel coordinate).
+///
+/// # Argument
+/// * `pix2depth` - depth buffer storing the NDC z (the larger, the closer).
+///   Initialize it with `f32::NEG_INFINITY` or `-1.` (the far plane).
+/// * `is_cull_back_face` - skip the triangles clockwise in the NDC
+/// * `fragment` - called as `fragment(i_pix, i_tri, &bc)` when the pixel passes the depth test,
+///   where `bc` is the perspective-correct barycentric coordinate of the triangle.
+///   It can be called several times for the same pixel as the closer triangles come.
+#[allow(clippy::too_many_arguments)]
+pub fn rasterize_with_depth_test<FRAG>(
+    img_shape: (usize, usize),
+    tri2vtx: &[usize],
+    vtx2xyz: &[f32],
+    transform_world2ndc: &[f32; 16],
+    is_cull_back_face: bool,
+    pix2depth: &mut [f32],
+    fragment: &mut FRAG,
+) where
+    FRAG: FnMut(usize, usize, &[f32; 3]),
+{
+    assert_eq!(pix2depth.len(), img_shape.0 * img_shape.1);
+    let ndc2pix = |c: &[f32; 4]| {
+        [
+            (c[0] / c[3] + 1.) * 0.5 * img_shape.0 as f32,
+            (1. - c[1] / c[3]) * 0.5 * img_shape.1 as f32,
+        ]
+    };
+    for (i_tri, node2vtx) in tri2vtx.chunks(3).enumerate() {
+        let vtx2clip: [[f32; 4]; 3] = std::array::from_fn(|i_node| {
+            let i_vtx = node2vtx[i_node];
+            let p = arrayref::array_ref![vtx2xyz, i_vtx * 3, 3];
+            del_geo_core::mat4_col_major::mult_vec(transform_world2ndc, &[p[0], p[1], p[2], 1.])
+        });
+        let poly = clip_near_plane(&vtx2clip);
+        if poly.len() < 3 || poly.iter().any(|v| v.0[3] <= 0.) {
+            continue;
+        }
+        let poly2pix: Vec<[f32; 2]> = poly.iter().map(|v| ndc2pix(&v.0)).collect();
+        for i_fan in 1..poly.len() - 1 {
+            let idx = [0, i_fan, i_fan + 1];
+            let (q0, q1, q2) = (&poly2pix[idx[0]], &poly2pix[idx[1]], &poly2pix[idx[2]]);
+            let area = del_geo_core::tri2::area(q0, q1, q2);
+            if area == 0. || (is_cull_back_face && area > 0.) {
+                continue;
+            }
+            // the pixel coordinate is upside down so the front face has the negative area
+            let sign = if area < 0. { -1f32 } else { 1f32 };
+            let aabbi = {
+                let aabb =
+                    crate::rasterize::polygon2::aabb2(&[q0[0], q0[1], q1[0], q1[1], q2[0], q2[1]]);
+                del_geo_core::aabb2::rasterize(&aabb, &img_shape)
+            };
+            let node2w = idx.map(|i| poly[i].0[3]);
+            let node2z = idx.map(|i| poly[i].0[2] / poly[i].0[3]);
+            for i_h in aabbi[1]..aabbi[3] {
+                for i_w in aabbi[0]..aabbi[2] {
+                    let p_xy = [i_w as f32 + 0.5, i_h as f32 + 0.5];
+                    let Some((r0, r1)) = del_geo_core::tri2::is_inside(q0, q1, q2, &p_xy, sign)
+                    else {
+                        continue;
+                    };
+                    let r = [r0, r1, 1. - r0 - r1];
+                    // the NDC depth is linear in the screen space
+                    let depth = r[0] * node2z[0] + r[1] * node2z[1] + r[2] * node2z[2];
+                    let i_pix = i_h * img_shape.0 + i_w;
+                    if depth < -1. || depth <= pix2depth[i_pix] {
+                        continue;
+                    }
+                    pix2depth[i_pix] = depth;
+                    let r = crate::rasterize::triangle2::barycentric_perspective_correct(
+                        &r,
+                        Some(&node2w),
+                    );
+                    let bc: [f32; 3] = std::array::from_fn(|k| {
+                        r[0] * poly[idx[0]].1[k]
+                            + r[1] * poly[idx[1]].1[k]
+                            + r[2] * poly[idx[2]].1[k]
+                    });
+                    fragment(i_pix, i_tri, &bc);
+                }
+            }
+        }
+    }
+}
+
+/// value of `pix2tri` for the pixels where no triangle is visible
+pub const NO_TRIANGLE: u32 = u32::MAX;
+
+/// render the color, depth and triangle index images of the triangle mesh
+///
+/// # Argument
+/// * `pix2depth` - depth buffer (see `rasterize_with_depth_test`)
+/// * `pix2tri` - index of the visible triangle. The background pixels are left untouched
+///   (initialize with `NO_TRIANGLE`)
+/// * `shader` - returns the color from the triangle index and the barycentric coordinate
+///   as `shader(i_tri, &bc)`. Gouraud shading can be done by interpolating the vertex colors with `bc`
+#[allow(clippy::too_many_arguments)]
+pub fn render<VAL, SHADER>(
+    img_shape: (usize, usize),
+    tri2vtx: &[usize],
+    vtx2xyz: &[f32],
+    transform_world2ndc: &[f32; 16],
+    is_cull_back_face: bool,
+    pix2color: &mut [VAL],
+    pix2depth: &mut [f32],
+    pix2tri: &mut [u32],
+    shader: &mut SHADER,
+) where
+    SHADER: FnMut(usize, &[f32; 3]) -> VAL,
+{
+    assert_eq!(pix2color.len(), img_shape.0 * img_shape.1);
+    assert_eq!(pix2tri.len(), img_shape.0 * img_shape.1);
+    rasterize_with_depth_test(
+        img_shape,
+        tri2vtx,
+        vtx2xyz,
+        transform_world2ndc,
+        is_cull_back_face,
+        pix2depth,
+        &mut |i_pix, i_tri, bc| {
+            pix2color[i_pix] = shader(i_tri, bc);
+            pix2tri[i_pix] = i_tri as u32;
+        },
+    );
+}
+
+/// G-buffer of the visible triangle and its barycentric coordinate at each pixel center,
+/// typically used for the differentiable rendering where the gradients are computed downstream.
+///
+/// The pixel centers are the same as those of the rays from `cam3::ray3_homogeneous`.
+/// returns `(pix2tri, pix2bc)` where
+/// * `pix2tri` - index of the visible triangle, or `NO_TRIANGLE` for the background
+/// * `pix2bc` - perspective-correct barycentric coordinates `[r0, r1]` of the first and second vertices
+///   of the triangle (`r2 = 1 - r0 - r1`), or `[0, 0]` for the background.
+pub fn pix2tri_bc(
+    img_shape: (usize, usize),
+    tri2vtx: &[usize],
+    vtx2xyz: &[f32],
+    transform_world2ndc: &[f32; 16],
+    is_cull_back_face: bool,
+) -> (Vec<u32>, Vec<[f32; 2]>) {
+    let num_pix = img_shape.0 * img_shape.1;
+    let mut pix2tri = vec![NO_TRIANGLE; num_pix];
+    let mut pix2bc = vec![[0f32; 2]; num_pix];
+    let mut pix2depth = vec![f32::NEG_INFINITY; num_pix];
+    rasterize_with_depth_test(
+        img_shape,
+        tri2vtx,
+        vtx2xyz,
+        transform_world2ndc,
+        is_cull_back_face,
+        &mut pix2depth,
+        &mut |i_pix, i_tri, bc| {
+            pix2tri[i_pix] = i_tri as u32;
+            pix2bc[i_pix] = [bc[0], bc[1]];
+        },
+    );
+    (pix2tri, pix2bc)
+}
+
+#[test]
+fn test0() -> anyhow::Result<()> {
+    let img_shape = (100usize, 100usize);
+    // two overlapping squares (the second one is closer to the camera) and a back-facing triangle
+    #[rustfmt::skip]
+    let vtx2xyz = [
+        -0.8f32, -0.8, 0.0, 0.4, -0.8, 0.0, 0.4, 0.4, 0.0, -0.8, 0.4, 0.0,
+        -0.4, -0.4, 0.5, 0.8, -0.4, 0.5, 0.8, 0.8, 0.5, -0.4, 0.8, 0.5,
+        -0.9, 0.5, 0.9, -0.5, 0.9, 0.9, -0.5, 0.5, 0.9,
+    ];
+    let tri2vtx = [0usize, 1, 2, 0, 2, 3, 4, 5, 6, 4, 6, 7, 8, 9, 10];
+    let transform_world2ndc: [f32; 16] = del_geo_core::mat4_col_major::from_identity();
+    let num_pix = img_shape.0 * img_shape.1;
+    let tri2rgb = [
+        [1f32, 0., 0.],
+        [1., 0., 0.],
+        [0., 0., 1.],
+        [0., 0., 1.],
+        [0., 1., 0.],
+    ];
+    for is_cull_back_face in [true, false] {
+        let mut pix2rgb = vec![[1f32; 3]; num_pix];
+        let mut pix2depth = vec![f32::NEG_INFINITY; num_pix];
+        let mut pix2tri = vec![NO_TRIANGLE; num_pix];
+        render(
+            img_shape,
+            &tri2vtx,
+            &vtx2xyz,
+            &transform_world2ndc,
+            is_cull_back_face,
+            &mut pix2rgb,
+            &mut pix2depth,
+            &mut pix2tri,
+            &mut |i_tri, _bc| tri2rgb[i_tri],
+        );
+        let pix = |x: f32, y: f32| {
+            let iw = ((x + 1.) * 0.5 * img_shape.0 as f32) as usize;
+            let ih = ((1. - y) * 0.5 * img_shape.1 as f32) as usize;
+            ih * img_shape.0 + iw
+        };
+        assert_eq!(pix2tri[pix(-0.6, -0.6)] / 2, 0);
+        assert_eq!(pix2tri[pix(0.2, 0.2)] / 2, 1); // closer square hides the farther one
+        assert!((pix2depth[pix(0.2, 0.2)] - 0.5).abs() < 1.0e-5);
+        assert_eq!(pix2tri[pix(0.6, -0.7)], NO_TRIANGLE);
+        if is_cull_back_face {
+            assert_eq!(pix2tri[pix(-0.6, 0.6)], NO_TRIANGLE);
+        } else {
+            assert_eq!(pix2tri[pix(-0.6, 0.6)], 4);
+        }
+        use slice_of_array::SliceFlatExt;
+        crate::write_png_from_float_image(
+            format!("target/rasterize_trimesh3-test0_{}.png", is_cull_back_face),
+            img_shape,
+            3,
+            pix2rgb.flat(),
+        )?;
+    }
+    Ok(())
+}
+
+#[test]
+fn test_clip_near_plane() {
+    // triangle partially in front of the near plane is cut at `z = +1`
+    let tri2vtx = [0usize, 1, 2];
+    let vtx2xyz = [-0.5f32, -0.5, 0.0, 0.5, -0.5, 0.0, 0.0, 0.5, 2.0];
+    let transform_world2ndc: [f32; 16] = del_geo_core::mat4_col_major::from_identity();
+    let mut pix2depth = vec![f32::NEG_INFINITY; 64 * 64];
+    let mut num_frag = 0;
+    let mut max_bc2 = 0f32;
+    rasterize_with_depth_test(
+        (64, 64),
+        &tri2vtx,
+        &vtx2xyz,
+        &transform_world2ndc,
+        true,
+        &mut pix2depth,
+        &mut |_i_pix, _i_tri, bc| {
+            num_frag += 1;
+            max_bc2 = max_bc2.max(bc[2]);
+        },
+    );
+    assert!(num_frag > 0);
+    assert!(max_bc2 <= 0.5 + 1.0e-5);
+    assert!(pix2depth.iter().all(|&d| d <= 1.0 + 1.0e-5));
+}
+
+#[test]
+fn test_pix2tri_bc() {
+    let img_shape = (60usize, 40usize);
+    let tri2vtx = [0usize, 1, 2, 0, 2, 3];
+    #[rustfmt::skip]
+    let vtx2xyz = [
+        -1.0f32, -0.8, -2.0, 1.0, -0.8, -4.0, 1.0, 0.8, -4.0, -1.0, 0.8, -2.0,
+    ];
+    // perspective camera at the origin looking at -z, mapping the near plane to `z=+1` in the NDC
+    let transform_world2ndc = {
+        let proj = del_geo_core::mat4_col_major::camera_perspective_blender(
+            img_shape.0 as f32 / img_shape.1 as f32,
+            24.,
+            0.5,
+            10.,
+            false,
+        );
+        let flip_z = del_geo_core::mat4_col_major::from_diagonal(1., 1., -1., 1.);
+        del_geo_core::mat4_col_major::mult_mat_col_major(&flip_z, &proj)
+    };
+    let transform_ndc2world =
+        del_geo_core::mat4_col_major::try_inverse(&transform_world2ndc).unwrap();
+    let (pix2tri, pix2bc) = pix2tri_bc(img_shape, &tri2vtx, &vtx2xyz, &transform_world2ndc, true);
+    let mut num_hit = 0;
+    for i_h in 0..img_shape.1 {
+        for i_w in 0..img_shape.0 {
+            let i_pix = i_h * img_shape.0 + i_w;
+            let (ray_org, ray_dir) =
+                crate::cam3::ray3_homogeneous((i_w, i_h), &img_shape, &transform_ndc2world);
+            let mut hit: Option<f32> = None;
+            for i_tri in 0..2 {
+                let p = |i_node: usize| {
+                    let i_vtx = tri2vtx[i_tri * 3 + i_node];
+                    *arrayref::array_ref![vtx2xyz, i_vtx * 3, 3]
+                };
+                let Some(t) = del_geo_core::tri3::intersection_against_line(
+                    &p(0),
+                    &p(1),
+                    &p(2),
+                    &ray_org,
+                    &ray_dir,
+                ) else {
+                    continue;
+                };
+                if hit.is_none() || t < hit.unwrap() {
+                    hit = Some(t);
+                }
+            }
+            let Some(t) = hit else {
+                assert_eq!(pix2tri[i_pix], NO_TRIANGLE);
+                continue;
+            };
+            if pix2tri[i_pix] == NO_TRIANGLE {
+                continue; // the ray grazes the boundary of the triangle
+            }
+            num_hit += 1;
+            let i_tri = pix2tri[i_pix] as usize; // either triangle on the diagonal edge
+            let p = |i_node: usize| {
+                let i_vtx = tri2vtx[i_tri * 3 + i_node];
+                *arrayref::array_ref![vtx2xyz, i_vtx * 3, 3]
+            };
+            use del_geo_core::vec3::Vec3;
+            let q = ray_org.add(&ray_dir.scale(t));
+            let bc = del_geo_core::tri3::to_barycentric_coords(&p(0), &p(1), &p(2), &q);
+            assert!((bc[0] - pix2bc[i_pix][0]).abs() < 1.0e-3, "{:?}", bc);
+            assert!((bc[1] - pix2bc[i_pix][1]).abs() < 1.0e-3, "{:?}", bc);
+        }
+    }
+    assert!(num_hit > 100);
+}
@@ -8,6 +8,29 @@ pub fn paint_one_pixel<Real, VAL>(
     i_color: VAL,
 ) where
     Real: num_traits::Float + AsPrimitive<usize>,
+    VAL: Copy,
+{
+    paint_one_pixel_blend(
+        pix2color,
+        img_width,
+        p0,
+        transform_xy2pix,
+        i_color,
+        &crate::blend::Overwrite,
+    );
+}
+
+pub fn paint_one_pixel_blend<Real, VAL, BLEND>(
+    pix2color: &mut [VAL],
+    img_width: usize,
+    p0: &[Real; 2],
+    transform_xy2pix: &[Real; 9],
+    i_color: VAL,
+    blend: &BLEND,
+) where
+    Real: num_traits::Float + AsPrimitive<usize>,
+    VAL: Copy,
+    BLEND: crate::blend::Blend<VAL>,
 {
     let img_height = pix2color.len() / img_width;
     let q0: [Real; 2] =
@@ -17,7 +40,7 @@ pub fn paint_one_pixel<Real, VAL>(
     if iw >= img_width || ih >= img_height {
         return;
     }
-    pix2color[ih * img_width + iw] = i_color;
+    blend.blend(&mut pix2color[ih * img_width + iw], i_color, 1.0);
 }
 
 pub fn paint_nxn_pixels<Real, VAL>(
@@ -30,6 +53,30 @@ pub fn paint_nxn_pixels<Real, VAL>(
 ) where
     Real: num_traits::Float + AsPrimitive<isize>,
     VAL: Copy,
+{
+    paint_nxn_pixels_blend(
+        pix2color,
+        img_width,
+        p0,
+        transform_xy2pix,
+        i_color,
+        n,
+        &crate::blend::Overwrite,
+    );
+}
+
+pub fn paint_nxn_pixels_blend<Real, VAL, BLEND>(
+    pix2color: &mut [VAL],
+    img_width: usize,
+    p0: &[Real; 2],
+    transform_xy2pix: &[Real; 9],
+    i_color: VAL,
+    n: isize,
+    blend: &BLEND,
+) where
+    Real: num_traits::Float + AsPrimitive<isize>,
+    VAL: Copy,
+    BLEND: crate::blend::Blend<VAL>,
 {
     let img_height = pix2color.len() / img_width;
     let q0: [Real; 2] =
@@ -46,7 +93,11 @@ pub fn paint_nxn_pixels<Real, VAL>(
             if j < 0 || j >= img_height as isize {
                 return;
             }
-            pix2color[(j * img_width as isize + i) as usize] = i_color;
+            blend.blend(
+                &mut pix2color[(j * img_width as isize + i) as usize],
+                i_color,
+                1.0,
+            );
         }
     }
 }
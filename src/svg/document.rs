@@ -0,0 +1,680 @@
+//! reading SVG documents and rasterizing them into a bitmap.
+//!
+//! Only the subset of SVG typically used for icons and annotations is supported:
+//! `<path>`, `<rect>`, `<circle>`, `<ellipse>`, `<line>`, `<polyline>`, `<polygon>` nested in `<g>`
+//! with the `transform` attribute. The `fill`, `stroke`, `stroke-width`, `fill-rule` and the opacity
+//! properties are read from both the presentation attributes and the `style` attribute.
+//! Style sheets, `<text>`, `<use>`, gradients and the contents of `<defs>` are ignored.
+
+use crate::color::Rgba;
+use crate::rasterize::polygon2::FillRule;
+use crate::svg::path::{Segment, SubPath};
+
+/// the properties of a shape inherited from its ancestors
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PaintStyle {
+    /// fill color. `None` for `fill="none"`
+    pub fill: Option<Rgba<u8>>,
+    /// stroke color. `None` for `stroke="none"`
+    pub stroke: Option<Rgba<u8>>,
+    /// stroke width in the local coordinate of the shape
+    pub stroke_width: f32,
+    pub fill_rule: FillRule,
+    /// product of the `opacity` of the element and its ancestors
+    pub opacity: f32,
+    pub fill_opacity: f32,
+    pub stroke_opacity: f32,
+}
+
+impl Default for PaintStyle {
+    fn default() -> Self {
+        PaintStyle {
+            fill: Some(Rgba::BLACK),
+            stroke: None,
+            stroke_width: 1.,
+            fill_rule: FillRule::NonZero,
+            opacity: 1.,
+            fill_opacity: 1.,
+            stroke_opacity: 1.,
+        }
+    }
+}
+
+impl PaintStyle {
+    /// set the property `name: value`. Unknown properties and invalid values are ignored
+    fn set_property(&mut self, name: &str, value: &str) {
+        let value = value.trim();
+        match name {
+            "fill" => {
+                if let Some(paint) = parse_paint(value) {
+                    self.fill = paint;
+                }
+            }
+            "stroke" => {
+                if let Some(paint) = parse_paint(value) {
+                    self.stroke = paint;
+                }
+            }
+            "stroke-width" => {
+                if let Some(v) = parse_length(value) {
+                    self.stroke_width = v;
+                }
+            }
+            "fill-rule" => match value {
+                "nonzero" => self.fill_rule = FillRule::NonZero,
+                "evenodd" => self.fill_rule = FillRule::EvenOdd,
+                _ => {}
+            },
+            "opacity" => {
+                if let Some(v) = parse_opacity(value) {
+                    self.opacity *= v;
+                }
+            }
+            "fill-opacity" => {
+                if let Some(v) = parse_opacity(value) {
+                    self.fill_opacity = v;
+                }
+            }
+            "stroke-opacity" => {
+                if let Some(v) = parse_opacity(value) {
+                    self.stroke_opacity = v;
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// a shape element converted into sub-paths
+#[derive(Debug, Clone)]
+pub struct Shape {
+    pub subpaths: Vec<SubPath>,
+    /// transformation from the local coordinate of the shape to the document (i.e., `viewBox`)
+    /// coordinate. 3x3 homogeneous transformation matrix with **column major** order
+    pub transform: [f32; 9],
+    pub style: PaintStyle,
+}
+
+#[derive(Debug, Clone)]
+pub struct Document {
+    pub width: f32,
+    pub height: f32,
+    /// `[min_x, min_y, width, height]`
+    pub view_box: [f32; 4],
+    /// shapes in the painting order
+    pub shapes: Vec<Shape>,
+}
+
+/// elements whose children are not rendered directly
+const NOT_RENDERED: [&str; 10] = [
+    "defs",
+    "clipPath",
+    "mask",
+    "marker",
+    "pattern",
+    "symbol",
+    "linearGradient",
+    "radialGradient",
+    "style",
+    "text",
+];
+
+impl Document {
+    pub fn load<P: AsRef<std::path::Path>>(path: P) -> anyhow::Result<Self> {
+        let s = std::fs::read_to_string(path)?;
+        Self::parse(&s)
+    }
+
+    pub fn parse(s: &str) -> anyhow::Result<Self> {
+        use del_geo_core::mat3_col_major;
+        let events = tokenize_xml(s)?;
+        let mut doc: Option<Document> = None;
+        // transformation and style of the ancestors
+        let mut stack: Vec<([f32; 9], PaintStyle)> = vec![];
+        // nesting depth inside the element that is not rendered
+        let mut depth_skip = 0usize;
+        for event in events {
+            let (name, attrs, is_empty) = match event {
+                XmlEvent::Start {
+                    name,
+                    attrs,
+                    is_empty,
+                } => (name, attrs, is_empty),
+                XmlEvent::End(_name) => {
+                    if depth_skip > 0 {
+                        depth_skip -= 1;
+                    } else {
+                        stack.pop();
+                    }
+                    continue;
+                }
+            };
+            if depth_skip > 0 || NOT_RENDERED.contains(&name) {
+                if !is_empty {
+                    depth_skip += 1;
+                }
+                continue;
+            }
+            let attr = |key: &str| {
+                attrs
+                    .iter()
+                    .rev()
+                    .find(|(k, _)| *k == key)
+                    .map(|(_, v)| v.as_str())
+            };
+            if doc.is_none() {
+                anyhow::ensure!(
+                    name == "svg",
+                    "the root element is not <svg> but <{}>",
+                    name
+                );
+                let width = attr("width").and_then(parse_length);
+                let height = attr("height").and_then(parse_length);
+                let view_box = match attr("viewBox") {
+                    Some(v) => match crate::svg::path::parse_numbers(v)?.as_slice() {
+                        &[x, y, w, h] => [x, y, w, h],
+                        _ => anyhow::bail!("invalid viewBox: {}", v),
+                    },
+                    None => [0., 0., width.unwrap_or(300.), height.unwrap_or(150.)],
+                };
+                doc = Some(Document {
+                    width: width.unwrap_or(view_box[2]),
+                    height: height.unwrap_or(view_box[3]),
+                    view_box,
+                    shapes: vec![],
+                });
+            }
+            let (mut transform, mut style) = stack
+                .last()
+                .copied()
+                .unwrap_or((mat3_col_major::from_identity(), PaintStyle::default()));
+            if let Some(v) = attr("transform") {
+                transform = mat3_col_major::mult_mat_col_major(&transform, &parse_transform(v)?);
+            }
+            {
+                // the `style` attribute has the priority over the presentation attributes
+                let mut props = std::collections::HashMap::new();
+                for (key, value) in attrs.iter() {
+                    props.insert(*key, value.as_str());
+                }
+                if let Some(v) = attr("style") {
+                    for (key, value) in v.split(';').filter_map(|decl| decl.split_once(':')) {
+                        props.insert(key.trim(), value);
+                    }
+                }
+                for (key, value) in props {
+                    style.set_property(key, value);
+                }
+            }
+            let subpaths = shape_to_subpaths(name, &attr)?;
+            if !subpaths.is_empty() {
+                doc.as_mut().unwrap().shapes.push(Shape {
+                    subpaths,
+                    transform,
+                    style,
+                });
+            }
+            if !is_empty {
+                stack.push((transform, style));
+            }
+        }
+        doc.ok_or_else(|| anyhow::anyhow!("no <svg> element"))
+    }
+
+    /// transformation from the document coordinate to the pixel coordinate fitting the `viewBox`
+    /// at the center of the image while preserving the aspect ratio.
+    /// 3x3 homogeneous transformation matrix with **column major** order
+    pub fn transform_doc2pix(&self, img_shape: (usize, usize)) -> [f32; 9] {
+        let [x, y, w, h] = self.view_box;
+        let (img_w, img_h) = (img_shape.0 as f32, img_shape.1 as f32);
+        let s = (img_w / w).min(img_h / h);
+        let tx = (img_w - w * s) * 0.5 - x * s;
+        let ty = (img_h - h * s) * 0.5 - y * s;
+        [s, 0., 0., 0., s, 0., tx, ty, 1.]
+    }
+
+    /// * `transform_doc2pix` - 3x3 homogeneous transformation matrix with **column major** order
+    pub fn rasterize(&self, img_data: &mut [[u8; 3]], width: usize, transform_doc2pix: &[f32; 9]) {
+        for shape in self.shapes.iter() {
+            shape.rasterize(img_data, width, transform_doc2pix);
+        }
+    }
+}
+
+impl Shape {
+    /// fill and then stroke the shape. The curves are flattened adaptively in the pixel coordinate.
+    /// Note that the joints of semi-transparent strokes are blended twice.
+    /// * `transform_doc2pix` - 3x3 homogeneous transformation matrix with **column major** order
+    pub fn rasterize(&self, img_data: &mut [[u8; 3]], width: usize, transform_doc2pix: &[f32; 9]) {
+        use crate::blend::Blend;
+        use slice_of_array::SliceFlatExt;
+        let transform =
+            del_geo_core::mat3_col_major::mult_mat_col_major(transform_doc2pix, &self.transform);
+        let loops: Vec<(Vec<f32>, bool)> = crate::svg::path::to_polybezier_loops(&self.subpaths)
+            .iter()
+            .map(|(vtx2xy, seg2vtx, is_closed)| {
+                let polyline = crate::canvas_svg::polybezier2polyloop_adaptive(
+                    vtx2xy, seg2vtx, *is_closed, &transform, 0.2,
+                );
+                (polyline.flat().to_vec(), *is_closed)
+            })
+            .collect();
+        if let Some(fill) = self.style.fill {
+            let opacity = self.style.opacity * self.style.fill_opacity * fill.opacity();
+            let loops: Vec<&[f32]> = loops.iter().map(|(vtx2xy, _)| vtx2xy.as_slice()).collect();
+            crate::rasterize::polygon2::fill_loops_blend(
+                img_data,
+                width,
+                &loops,
+                &transform,
+                self.style.fill_rule,
+                fill.rgb(),
+                &|dst: &mut [u8; 3], src: [u8; 3], coverage: f32| {
+                    crate::blend::BlendMode::Overwrite.blend(dst, src, coverage * opacity)
+                },
+            );
+        }
+        if let Some(stroke) = self.style.stroke {
+            let opacity = self.style.opacity * self.style.stroke_opacity * stroke.opacity();
+            let scale = del_geo_core::mat3_col_major::determinant(&transform)
+                .abs()
+                .sqrt();
+            let thickness = 0.5 * self.style.stroke_width * scale;
+            for (vtx2xy, is_closed) in loops.iter() {
+                let num_vtx = vtx2xy.len() / 2;
+                if num_vtx < 2 {
+                    continue;
+                }
+                let num_edge = if *is_closed { num_vtx } else { num_vtx - 1 };
+                for i_edge in 0..num_edge {
+                    let j_vtx = (i_edge + 1) % num_vtx;
+                    crate::rasterize::line2::draw_pixcenter_antialias(
+                        img_data,
+                        width,
+                        arrayref::array_ref![vtx2xy, i_edge * 2, 2],
+                        arrayref::array_ref![vtx2xy, j_vtx * 2, 2],
+                        &transform,
+                        thickness,
+                        stroke.rgb(),
+                        &|dst: &mut [u8; 3], src: [u8; 3], coverage: f32| {
+                            crate::blend::BlendMode::Overwrite.blend(dst, src, coverage * opacity)
+                        },
+                    );
+                }
+            }
+        }
+    }
+}
+
+/// convert the basic shape element into sub-paths. Returns an empty vector for non-shape elements
+fn shape_to_subpaths<'a, F>(name: &str, attr: &F) -> anyhow::Result<Vec<SubPath>>
+where
+    F: Fn(&str) -> Option<&'a str>,
+{
+    let num = |key: &str| attr(key).and_then(parse_length);
+    let arc = |rx: f32, ry: f32, end: [f32; 2]| Segment::Arc {
+        radii: [rx, ry],
+        x_axis_rotation: 0.,
+        large_arc: false,
+        sweep: true,
+        end,
+    };
+    let ellipse = |cx: f32, cy: f32, rx: f32, ry: f32| {
+        if rx <= 0. || ry <= 0. {
+            return vec![];
+        }
+        vec![SubPath {
+            start: [cx + rx, cy],
+            segments: vec![arc(rx, ry, [cx - rx, cy]), arc(rx, ry, [cx + rx, cy])],
+            is_closed: true,
+        }]
+    };
+    let subpaths = match name {
+        "path" => crate::svg::path::parse(attr("d").unwrap_or(""))?,
+        "rect" => {
+            let (x, y) = (num("x").unwrap_or(0.), num("y").unwrap_or(0.));
+            let (w, h) = (num("width").unwrap_or(0.), num("height").unwrap_or(0.));
+            if w <= 0. || h <= 0. {
+                return Ok(vec![]);
+            }
+            let (rx, ry) = match (num("rx"), num("ry")) {
+                (Some(rx), Some(ry)) => (rx, ry),
+                (Some(r), None) | (None, Some(r)) => (r, r),
+                (None, None) => (0., 0.),
+            };
+            let (rx, ry) = (rx.clamp(0., w * 0.5), ry.clamp(0., h * 0.5));
+            let segments = if rx == 0. || ry == 0. {
+                vec![
+                    Segment::Line([x + w, y]),
+                    Segment::Line([x + w, y + h]),
+                    Segment::Line([x, y + h]),
+                ]
+            } else {
+                vec![
+                    Segment::Line([x + w - rx, y]),
+                    arc(rx, ry, [x + w, y + ry]),
+                    Segment::Line([x + w, y + h - ry]),
+                    arc(rx, ry, [x + w - rx, y + h]),
+                    Segment::Line([x + rx, y + h]),
+                    arc(rx, ry, [x, y + h - ry]),
+                    Segment::Line([x, y + ry]),
+                    arc(rx, ry, [x + rx, y]),
+                ]
+            };
+            vec![SubPath {
+                start: [x + rx, y],
+                segments,
+                is_closed: true,
+            }]
+        }
+        "circle" => {
+            let r = num("r").unwrap_or(0.);
+            ellipse(num("cx").unwrap_or(0.), num("cy").unwrap_or(0.), r, r)
+        }
+        "ellipse" => ellipse(
+            num("cx").unwrap_or(0.),
+            num("cy").unwrap_or(0.),
+            num("rx").unwrap_or(0.),
+            num("ry").unwrap_or(0.),
+        ),
+        "line" => vec![SubPath {
+            start: [num("x1").unwrap_or(0.), num("y1").unwrap_or(0.)],
+            segments: vec![Segment::Line([
+                num("x2").unwrap_or(0.),
+                num("y2").unwrap_or(0.),
+            ])],
+            is_closed: false,
+        }],
+        "polyline" | "polygon" => {
+            let vals = crate::svg::path::parse_numbers(attr("points").unwrap_or(""))?;
+            // the odd coordinate at the end is ignored
+            let vtx2xy: Vec<[f32; 2]> = vals.chunks_exact(2).map(|v| [v[0], v[1]]).collect();
+            if vtx2xy.len() < 2 {
+                return Ok(vec![]);
+            }
+            vec![SubPath {
+                start: vtx2xy[0],
+                segments: vtx2xy[1..].iter().map(|&p| Segment::Line(p)).collect(),
+                is_closed: name == "polygon",
+            }]
+        }
+        _ => vec![],
+    };
+    Ok(subpaths)
+}
+
+/// parse the `transform` attribute such as `translate(10,20) rotate(45)` into
+/// the 3x3 homogeneous transformation matrix with **column major** order
+pub fn parse_transform(s: &str) -> anyhow::Result<[f32; 9]> {
+    use del_geo_core::mat3_col_major;
+    let mut transform = mat3_col_major::from_identity();
+    let mut rest = s;
+    loop {
+        rest = rest.trim_start_matches(|c: char| c.is_ascii_whitespace() || c == ',');
+        if rest.is_empty() {
+            break;
+        }
+        let (name, args, after) = rest
+            .split_once('(')
+            .and_then(|(name, after)| {
+                let (args, after) = after.split_once(')')?;
+                Some((name.trim(), args, after))
+            })
+            .ok_or_else(|| anyhow::anyhow!("invalid transform: {}", s))?;
+        let args = crate::svg::path::parse_numbers(args)?;
+        let t = match (name, args.as_slice()) {
+            ("matrix", &[a, b, c, d, e, f]) => [a, b, 0., c, d, 0., e, f, 1.],
+            ("translate", &[tx]) => [1., 0., 0., 0., 1., 0., tx, 0., 1.],
+            ("translate", &[tx, ty]) => [1., 0., 0., 0., 1., 0., tx, ty, 1.],
+            ("scale", &[s]) => [s, 0., 0., 0., s, 0., 0., 0., 1.],
+            ("scale", &[sx, sy]) => [sx, 0., 0., 0., sy, 0., 0., 0., 1.],
+            ("rotate", &[a]) | ("rotate", &[a, _, _]) => {
+                let (sin, cos) = a.to_radians().sin_cos();
+                let (cx, cy) = if args.len() == 3 {
+                    (args[1], args[2])
+                } else {
+                    (0., 0.)
+                };
+                // translate(cx,cy) rotate(a) translate(-cx,-cy)
+                [
+                    cos,
+                    sin,
+                    0.,
+                    -sin,
+                    cos,
+                    0.,
+                    cx - cos * cx + sin * cy,
+                    cy - sin * cx - cos * cy,
+                    1.,
+                ]
+            }
+            ("skewX", &[a]) => [1., 0., 0., a.to_radians().tan(), 1., 0., 0., 0., 1.],
+            ("skewY", &[a]) => [1., a.to_radians().tan(), 0., 0., 1., 0., 0., 0., 1.],
+            _ => anyhow::bail!("invalid transform: {}", s),
+        };
+        transform = mat3_col_major::mult_mat_col_major(&transform, &t);
+        rest = after;
+    }
+    Ok(transform)
+}
+
+/// `Some(None)` for `none` and `None` for the invalid value
+fn parse_paint(s: &str) -> Option<Option<Rgba<u8>>> {
+    match s.trim() {
+        "none" => Some(None),
+        // the `color` property is not supported
+        "currentColor" => Some(Some(Rgba::BLACK)),
+        s => Rgba::parse_css(s).map(Some),
+    }
+}
+
+/// length in the user unit. The `px` suffix is allowed but the other units are not supported
+fn parse_length(s: &str) -> Option<f32> {
+    let s = s.trim();
+    s.strip_suffix("px").unwrap_or(s).trim().parse::<f32>().ok()
+}
+
+/// opacity either as a number or a percentage clamped into `[0,1]`
+fn parse_opacity(s: &str) -> Option<f32> {
+    let v = match s.strip_suffix('%') {
+        Some(v) => v.trim().parse::<f32>().ok()? * 0.01,
+        None => s.parse::<f32>().ok()?,
+    };
+    Some(v.clamp(0., 1.))
+}
+
+// ---------------------------
+// minimal XML tokenizer
+
+enum XmlEvent<'a> {
+    Start {
+        name: &'a str,
+        attrs: Vec<(&'a str, String)>,
+        /// the element is closed by `/>`
+        is_empty: bool,
+    },
+    End(&'a str),
+}
+
+/// split the XML into the start and end tags. Comments, processing instructions, `<!DOCTYPE>`
+/// and the text contents are skipped.
+fn tokenize_xml(s: &str) -> anyhow::Result<Vec<XmlEvent<'_>>> {
+    let bytes = s.as_bytes();
+    let mut events = vec![];
+    let mut pos = 0;
+    while let Some(i) = s[pos..].find('<') {
+        let start = pos + i;
+        let rest = &s[start..];
+        let skip_until = |end: &str| {
+            rest.find(end)
+                .map(|i| start + i + end.len())
+                .ok_or_else(|| anyhow::anyhow!("unterminated markup at {}", start))
+        };
+        if rest.starts_with("<!--") {
+            pos = skip_until("-->")?;
+            continue;
+        }
+        if rest.starts_with("<![CDATA[") {
+            pos = skip_until("]]>")?;
+            continue;
+        }
+        if rest.starts_with("<?") {
+            pos = skip_until("?>")?;
+            continue;
+        }
+        if rest.starts_with("<!") {
+            pos = skip_until(">")?;
+            continue;
+        }
+        // find the closing `>` outside the quoted attribute values
+        let mut end = start + 1;
+        let mut quote: Option<u8> = None;
+        loop {
+            let Some(&c) = bytes.get(end) else {
+                anyhow::bail!("unterminated tag at {}", start);
+            };
+            match quote {
+                Some(q) if c == q => quote = None,
+                Some(_) => {}
+                None if c == b'"' || c == b'\'' => quote = Some(c),
+                None if c == b'>' => break,
+                None => {}
+            }
+            end += 1;
+        }
+        let tag = &s[start + 1..end];
+        pos = end + 1;
+        if let Some(name) = tag.strip_prefix('/') {
+            events.push(XmlEvent::End(name.trim()));
+            continue;
+        }
+        let (tag, is_empty) = match tag.strip_suffix('/') {
+            Some(tag) => (tag, true),
+            None => (tag, false),
+        };
+        let name_end = tag
+            .find(|c: char| c.is_ascii_whitespace())
+            .unwrap_or(tag.len());
+        let name = &tag[..name_end];
+        anyhow::ensure!(!name.is_empty(), "empty tag name at {}", start);
+        events.push(XmlEvent::Start {
+            name,
+            attrs: parse_xml_attributes(&tag[name_end..])
+                .ok_or_else(|| anyhow::anyhow!("invalid attributes in <{}> at {}", name, start))?,
+            is_empty,
+        });
+    }
+    Ok(events)
+}
+
+fn parse_xml_attributes(s: &str) -> Option<Vec<(&str, String)>> {
+    let mut attrs = vec![];
+    let mut rest = s.trim_start();
+    while !rest.is_empty() {
+        let (key, after) = rest.split_once('=')?;
+        let after = after.trim_start();
+        let quote = after.chars().next().filter(|&c| c == '"' || c == '\'')?;
+        let (value, after) = after[1..].split_once(quote)?;
+        attrs.push((key.trim(), unescape_xml(value)));
+        rest = after.trim_start();
+    }
+    Some(attrs)
+}
+
+/// replace the predefined entities and the character references
+fn unescape_xml(s: &str) -> String {
+    let mut res = String::with_capacity(s.len());
+    let mut rest = s;
+    while let Some(i) = rest.find('&') {
+        res.push_str(&rest[..i]);
+        rest = &rest[i..];
+        let Some(end) = rest.find(';') else {
+            break;
+        };
+        let c = match &rest[1..end] {
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "amp" => Some('&'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            r => r
+                .strip_prefix("#x")
+                .map(|h| u32::from_str_radix(h, 16).ok())
+                .unwrap_or_else(|| r.strip_prefix('#').and_then(|d| d.parse::<u32>().ok()))
+                .and_then(char::from_u32),
+        };
+        match c {
+            Some(c) => {
+                res.push(c);
+                rest = &rest[end + 1..];
+            }
+            None => {
+                res.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    res.push_str(rest);
+    res
+}
+
+#[test]
+fn test_parse_transform() {
+    let t = parse_transform("rotate(90 10 10)").unwrap();
+    let p = del_geo_core::mat3_col_major::transform_homogeneous(&t, &[20., 10.]).unwrap();
+    assert!((p[0] - 10.).abs() < 1.0e-5 && (p[1] - 20.).abs() < 1.0e-5);
+    let t = parse_transform("translate(1, 2),scale(2) matrix(1 0 0 1 3 4)").unwrap();
+    let p = del_geo_core::mat3_col_major::transform_homogeneous(&t, &[0., 0.]).unwrap();
+    assert_eq!(p, [7., 10.]);
+    assert!(parse_transform("translate(1,2").is_err());
+    //
+    assert_eq!(parse_paint("none"), Some(None));
+    assert_eq!(parse_paint("#ff8800"), Some(Some(Rgba::from(0xff8800))));
+    assert_eq!(
+        parse_paint("#ff88"),
+        Some(Some(Rgba::new(255, 255, 136, 136)))
+    );
+    assert_eq!(parse_paint("unknown"), None);
+    assert_eq!(unescape_xml("a&lt;b &amp;&#65;&#x42; & c"), "a<b &AB & c");
+}
+
+#[test]
+fn test_document() -> anyhow::Result<()> {
+    let s = r##"<?xml version="1.0" encoding="UTF-8"?>
+<!-- test document -->
+<svg xmlns="http://www.w3.org/2000/svg" width="128px" height="64" viewBox="0 0 128 64">
+  <defs><rect width="128" height="64" fill="red"/></defs>
+  <rect width="128" height="64" fill="#fff"/>
+  <g transform="translate(64 0)" style="fill: blue">
+    <rect x="8" y="8" width="48" height="48" rx="8" title="a &gt; b"/>
+    <circle cx="32" cy="32" r="12" fill="#0f0" stroke="black" stroke-width="4"/>
+    <g opacity="0.5"><line x1="8" y1="60" x2="56" y2="60" stroke="black" stroke-width="2"/></g>
+  </g>
+  <path d="M8 8 h48 v48 h-48 z M20 20 h24 v24 h-24 z" fill-rule="evenodd" fill="rgb(255,0,0)"/>
+</svg>"##;
+    let doc = Document::parse(s)?;
+    assert_eq!(doc.shapes.len(), 5);
+    assert_eq!((doc.width, doc.height), (128., 64.));
+    assert_eq!(doc.shapes[1].style.fill, Some(Rgba::from(0x0000ff)));
+    assert_eq!(doc.shapes[3].style.opacity, 0.5);
+    let img_shape = (256usize, 128usize);
+    let transform_doc2pix = doc.transform_doc2pix(img_shape);
+    let mut img_data = vec![[0u8; 3]; img_shape.0 * img_shape.1];
+    doc.rasterize(&mut img_data, img_shape.0, &transform_doc2pix);
+    let pix = |x: usize, y: usize| img_data[y * 2 * img_shape.0 + x * 2];
+    assert_eq!(pix(32, 32), [255, 255, 255]); // hole by the even-odd rule
+    assert_eq!(pix(12, 32), [255, 0, 0]);
+    assert_eq!(pix(76, 32), [0, 0, 255]);
+    assert_eq!(pix(96, 32), [0, 255, 0]);
+    assert_eq!(pix(96, 19), [0, 0, 0]); // stroke of the circle
+    assert_eq!(pix(100, 60), [128, 128, 128]); // semi-transparent line
+    assert_eq!(pix(65, 9), [255, 255, 255]); // rounded corner
+    let img: image::RgbImage = image::ImageBuffer::from_raw(
+        img_shape.0 as u32,
+        img_shape.1 as u32,
+        slice_of_array::SliceFlatExt::flat(img_data.as_slice()).to_vec(),
+    )
+    .unwrap();
+    img.save("target/svg_document.png")?;
+    Ok(())
+}
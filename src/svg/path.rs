@@ -0,0 +1,584 @@
+//! parser of the SVG 1.1 path data (i.e., the `d` attribute of the `<path>` element)
+//!
+//! All the coordinates are converted into absolute ones and the shorthand commands
+//! (`H`, `V`, `S`, `T` and their relative versions) are expanded into the basic segments.
+
+/// segment of a sub-path starting from the end point of the previous segment
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Segment {
+    /// end point
+    Line([f32; 2]),
+    /// control point and end point
+    Quadratic([f32; 2], [f32; 2]),
+    /// two control points and end point
+    Cubic([f32; 2], [f32; 2], [f32; 2]),
+    /// elliptical arc parameterized by its end point
+    Arc {
+        radii: [f32; 2],
+        /// rotation of the x-axis of the ellipse in degrees
+        x_axis_rotation: f32,
+        large_arc: bool,
+        sweep: bool,
+        end: [f32; 2],
+    },
+}
+
+impl Segment {
+    pub fn end(&self) -> [f32; 2] {
+        match self {
+            Segment::Line(p) => *p,
+            Segment::Quadratic(_, p) => *p,
+            Segment::Cubic(_, _, p) => *p,
+            Segment::Arc { end, .. } => *end,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SubPath {
+    pub start: [f32; 2],
+    pub segments: Vec<Segment>,
+    /// closed by `Z` or `z`
+    pub is_closed: bool,
+}
+
+impl SubPath {
+    pub fn end(&self) -> [f32; 2] {
+        self.segments.last().map_or(self.start, |s| s.end())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ParseErrorKind {
+    UnexpectedCharacter(char),
+    UnexpectedEnd,
+    ExpectedNumber,
+    ExpectedFlag,
+    /// the path data does not start with `M` or `m`
+    MissingMoveTo,
+}
+
+/// error of the path data with the byte position where it happens
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseError {
+    pub position: usize,
+    pub kind: ParseErrorKind,
+}
+
+impl std::fmt::Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.kind {
+            ParseErrorKind::UnexpectedCharacter(c) => {
+                write!(f, "unexpected character '{}'", c)?;
+            }
+            ParseErrorKind::UnexpectedEnd => write!(f, "unexpected end of the path data")?,
+            ParseErrorKind::ExpectedNumber => write!(f, "expected a number")?,
+            ParseErrorKind::ExpectedFlag => write!(f, "expected a flag (0 or 1)")?,
+            ParseErrorKind::MissingMoveTo => write!(f, "the path data should start with moveto")?,
+        }
+        write!(f, " at position {} of the SVG path data", self.position)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+struct Lexer<'a> {
+    s: &'a [u8],
+    pos: usize,
+}
+
+impl Lexer<'_> {
+    fn error(&self, kind: ParseErrorKind) -> ParseError {
+        ParseError {
+            position: self.pos,
+            kind,
+        }
+    }
+
+    fn skip_wsp(&mut self) {
+        while self.pos < self.s.len() && matches!(self.s[self.pos], b' ' | b'\t' | b'\n' | b'\r') {
+            self.pos += 1;
+        }
+    }
+
+    /// whitespaces with an optional comma
+    fn skip_comma_wsp(&mut self) {
+        self.skip_wsp();
+        if self.pos < self.s.len() && self.s[self.pos] == b',' {
+            self.pos += 1;
+            self.skip_wsp();
+        }
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.s.get(self.pos).copied()
+    }
+
+    fn is_number_start(&self) -> bool {
+        matches!(self.peek(), Some(b'0'..=b'9' | b'.' | b'-' | b'+'))
+    }
+
+    fn digits(&mut self) -> usize {
+        let start = self.pos;
+        while matches!(self.peek(), Some(b'0'..=b'9')) {
+            self.pos += 1;
+        }
+        self.pos - start
+    }
+
+    /// number such as `-1.5`, `.5`, `1e-3`. Note that `0.5.5` is two numbers `0.5` and `.5`
+    fn number(&mut self) -> Result<f32, ParseError> {
+        self.skip_wsp();
+        let start = self.pos;
+        if self.peek().is_none() {
+            return Err(self.error(ParseErrorKind::UnexpectedEnd));
+        }
+        if matches!(self.peek(), Some(b'-' | b'+')) {
+            self.pos += 1;
+        }
+        let mut num_digit = self.digits();
+        if self.peek() == Some(b'.') {
+            self.pos += 1;
+            num_digit += self.digits();
+        }
+        if num_digit == 0 {
+            self.pos = start;
+            return Err(self.error(ParseErrorKind::ExpectedNumber));
+        }
+        if matches!(self.peek(), Some(b'e' | b'E')) {
+            let pos_e = self.pos;
+            self.pos += 1;
+            if matches!(self.peek(), Some(b'-' | b'+')) {
+                self.pos += 1;
+            }
+            if self.digits() == 0 {
+                self.pos = pos_e; // `e` is not a part of the number
+            }
+        }
+        let v = std::str::from_utf8(&self.s[start..self.pos])
+            .unwrap()
+            .parse::<f32>()
+            .unwrap();
+        self.skip_comma_wsp();
+        Ok(v)
+    }
+
+    fn point(&mut self) -> Result<[f32; 2], ParseError> {
+        let x = self.number()?;
+        let y = self.number()?;
+        Ok([x, y])
+    }
+
+    /// flag of the arc command. It can be followed by the next number without any separator
+    fn flag(&mut self) -> Result<bool, ParseError> {
+        self.skip_wsp();
+        let v = match self.peek() {
+            Some(b'0') => false,
+            Some(b'1') => true,
+            None => return Err(self.error(ParseErrorKind::UnexpectedEnd)),
+            _ => return Err(self.error(ParseErrorKind::ExpectedFlag)),
+        };
+        self.pos += 1;
+        self.skip_comma_wsp();
+        Ok(v)
+    }
+}
+
+/// parse the list of numbers separated by comma and/or whitespaces (e.g., `points` attribute)
+pub(crate) fn parse_numbers(s: &str) -> Result<Vec<f32>, ParseError> {
+    let mut lex = Lexer {
+        s: s.as_bytes(),
+        pos: 0,
+    };
+    let mut res = vec![];
+    lex.skip_wsp();
+    while lex.peek().is_some() {
+        res.push(lex.number()?);
+    }
+    Ok(res)
+}
+
+/// parse the path data into sub-paths whose coordinates are all absolute
+///
+/// ```
+/// let path = del_canvas::svg::path::parse("M 0,0 h 10 v 10 z").unwrap();
+/// assert_eq!(path.len(), 1);
+/// assert_eq!(path[0].segments.len(), 2);
+/// assert!(path[0].is_closed);
+/// ```
+pub fn parse(d: &str) -> Result<Vec<SubPath>, ParseError> {
+    use del_geo_core::vec2::Vec2;
+    let mut lex = Lexer {
+        s: d.as_bytes(),
+        pos: 0,
+    };
+    let mut subpaths: Vec<SubPath> = vec![];
+    let mut pos_cur = [0f32; 2];
+    // control point to be reflected by `S` and `T`
+    let mut prev_cubic_ctrl: Option<[f32; 2]> = None;
+    let mut prev_quad_ctrl: Option<[f32; 2]> = None;
+    let mut cmd: Option<u8> = None;
+    lex.skip_wsp();
+    loop {
+        lex.skip_wsp();
+        let Some(c) = lex.peek() else {
+            break;
+        };
+        if c.is_ascii_alphabetic() {
+            if !b"MmZzLlHhVvCcSsQqTtAa".contains(&c) {
+                return Err(lex.error(ParseErrorKind::UnexpectedCharacter(c as char)));
+            }
+            if cmd.is_none() && c != b'M' && c != b'm' {
+                return Err(lex.error(ParseErrorKind::MissingMoveTo));
+            }
+            lex.pos += 1;
+            lex.skip_wsp();
+            cmd = Some(c);
+        } else if !lex.is_number_start() {
+            return Err(lex.error(ParseErrorKind::UnexpectedCharacter(c as char)));
+        } else {
+            // the parameters are repeated for the previous command except for the closepath
+            match cmd {
+                None => return Err(lex.error(ParseErrorKind::MissingMoveTo)),
+                Some(b'Z' | b'z') => {
+                    return Err(lex.error(ParseErrorKind::UnexpectedCharacter(c as char)));
+                }
+                _ => {}
+            }
+        }
+        let c = cmd.unwrap();
+        let is_relative = c.is_ascii_lowercase();
+        let origin = if is_relative { pos_cur } else { [0f32; 2] };
+        // start a new sub-path if the previous one is closed without `M`
+        if !matches!(c, b'M' | b'm' | b'Z' | b'z')
+            && !matches!(subpaths.last(), Some(s) if !s.is_closed)
+        {
+            subpaths.push(SubPath {
+                start: pos_cur,
+                segments: vec![],
+                is_closed: false,
+            });
+        }
+        let mut cubic_ctrl: Option<[f32; 2]> = None;
+        let mut quad_ctrl: Option<[f32; 2]> = None;
+        let segment = match c {
+            b'M' | b'm' => {
+                pos_cur = lex.point()?.add(&origin);
+                subpaths.push(SubPath {
+                    start: pos_cur,
+                    segments: vec![],
+                    is_closed: false,
+                });
+                // the following coordinate pairs are the implicit lineto commands
+                cmd = Some(if is_relative { b'l' } else { b'L' });
+                None
+            }
+            b'Z' | b'z' => {
+                if let Some(subpath) = subpaths.last_mut() {
+                    subpath.is_closed = true;
+                    pos_cur = subpath.start;
+                }
+                None
+            }
+            b'L' | b'l' => Some(Segment::Line(lex.point()?.add(&origin))),
+            b'H' => Some(Segment::Line([lex.number()?, pos_cur[1]])),
+            b'h' => Some(Segment::Line([pos_cur[0] + lex.number()?, pos_cur[1]])),
+            b'V' => Some(Segment::Line([pos_cur[0], lex.number()?])),
+            b'v' => Some(Segment::Line([pos_cur[0], pos_cur[1] + lex.number()?])),
+            b'C' | b'c' => {
+                let p1 = lex.point()?.add(&origin);
+                let p2 = lex.point()?.add(&origin);
+                let p3 = lex.point()?.add(&origin);
+                cubic_ctrl = Some(p2);
+                Some(Segment::Cubic(p1, p2, p3))
+            }
+            b'S' | b's' => {
+                let p1 = prev_cubic_ctrl.map_or(pos_cur, |q| pos_cur.scale(2.).sub(&q));
+                let p2 = lex.point()?.add(&origin);
+                let p3 = lex.point()?.add(&origin);
+                cubic_ctrl = Some(p2);
+                Some(Segment::Cubic(p1, p2, p3))
+            }
+            b'Q' | b'q' => {
+                let p1 = lex.point()?.add(&origin);
+                let p2 = lex.point()?.add(&origin);
+                quad_ctrl = Some(p1);
+                Some(Segment::Quadratic(p1, p2))
+            }
+            b'T' | b't' => {
+                let p1 = prev_quad_ctrl.map_or(pos_cur, |q| pos_cur.scale(2.).sub(&q));
+                let p2 = lex.point()?.add(&origin);
+                quad_ctrl = Some(p1);
+                Some(Segment::Quadratic(p1, p2))
+            }
+            b'A' | b'a' => {
+                let rx = lex.number()?;
+                let ry = lex.number()?;
+                let x_axis_rotation = lex.number()?;
+                let large_arc = lex.flag()?;
+                let sweep = lex.flag()?;
+                let end = lex.point()?.add(&origin);
+                Some(Segment::Arc {
+                    radii: [rx, ry],
+                    x_axis_rotation,
+                    large_arc,
+                    sweep,
+                    end,
+                })
+            }
+            _ => unreachable!(),
+        };
+        if let Some(segment) = segment {
+            pos_cur = segment.end();
+            subpaths.last_mut().unwrap().segments.push(segment);
+        }
+        prev_cubic_ctrl = cubic_ctrl;
+        prev_quad_ctrl = quad_ctrl;
+    }
+    Ok(subpaths)
+}
+
+/// convert the elliptical arc from `p0` into cubic Bézier segments
+/// following the endpoint-to-center conversion of the SVG implementation notes.
+///
+/// * returns no segment if the end points are the same,
+///   and a line if one of the radii is zero
+/// * the radii are scaled up if they are too small to connect the end points
+/// * each cubic Bézier spans at most a quarter of the ellipse
+pub fn arc_to_cubics(
+    p0: &[f32; 2],
+    radii: &[f32; 2],
+    x_axis_rotation: f32,
+    large_arc: bool,
+    sweep: bool,
+    p1: &[f32; 2],
+) -> Vec<Segment> {
+    use std::f32::consts::PI;
+    if p0 == p1 {
+        return vec![];
+    }
+    let (mut rx, mut ry) = (radii[0].abs(), radii[1].abs());
+    if rx == 0. || ry == 0. {
+        return vec![Segment::Line(*p1)];
+    }
+    let (sin_phi, cos_phi) = x_axis_rotation.to_radians().sin_cos();
+    // end point in the coordinate aligned with the ellipse axes, centered at the mid-point
+    let dx = (p0[0] - p1[0]) * 0.5;
+    let dy = (p0[1] - p1[1]) * 0.5;
+    let x1 = cos_phi * dx + sin_phi * dy;
+    let y1 = -sin_phi * dx + cos_phi * dy;
+    let lambda = (x1 * x1) / (rx * rx) + (y1 * y1) / (ry * ry);
+    if lambda > 1. {
+        rx *= lambda.sqrt();
+        ry *= lambda.sqrt();
+    }
+    let coef = {
+        let num = rx * rx * ry * ry - rx * rx * y1 * y1 - ry * ry * x1 * x1;
+        let den = rx * rx * y1 * y1 + ry * ry * x1 * x1;
+        let coef = (num / den).max(0.).sqrt();
+        if large_arc == sweep {
+            -coef
+        } else {
+            coef
+        }
+    };
+    let cx1 = coef * rx * y1 / ry;
+    let cy1 = -coef * ry * x1 / rx;
+    let center = [
+        cos_phi * cx1 - sin_phi * cy1 + (p0[0] + p1[0]) * 0.5,
+        sin_phi * cx1 + cos_phi * cy1 + (p0[1] + p1[1]) * 0.5,
+    ];
+    let theta0 = ((y1 - cy1) / ry).atan2((x1 - cx1) / rx);
+    let mut dtheta = ((-y1 - cy1) / ry).atan2((-x1 - cx1) / rx) - theta0;
+    if sweep && dtheta < 0. {
+        dtheta += 2. * PI;
+    } else if !sweep && dtheta > 0. {
+        dtheta -= 2. * PI;
+    }
+    // point on the ellipse from the point on the unit circle
+    let map = |x: f32, y: f32| {
+        [
+            center[0] + cos_phi * rx * x - sin_phi * ry * y,
+            center[1] + sin_phi * rx * x + cos_phi * ry * y,
+        ]
+    };
+    let num_seg = (dtheta.abs() / (PI * 0.5) - 1.0e-5).ceil().max(1.) as usize;
+    let delta = dtheta / num_seg as f32;
+    let k = 4. / 3. * (delta * 0.25).tan();
+    let mut res = Vec::with_capacity(num_seg);
+    for i_seg in 0..num_seg {
+        let ta = theta0 + delta * i_seg as f32;
+        let tb = ta + delta;
+        let (sa, ca) = ta.sin_cos();
+        let (sb, cb) = tb.sin_cos();
+        let end = if i_seg == num_seg - 1 {
+            *p1
+        } else {
+            map(cb, sb)
+        };
+        res.push(Segment::Cubic(
+            map(ca - k * sa, sa + k * ca),
+            map(cb + k * sb, sb - k * cb),
+            end,
+        ));
+    }
+    res
+}
+
+/// convert sub-paths into the poly-Bézier loops used in `canvas_svg::polybezier2polyloop_adaptive`.
+/// Each loop is `(vtx2xy, seg2vtx, is_closed)` where the `i`-th segment has the vertices from
+/// `seg2vtx[i]` to `seg2vtx[i+1]` (two vertices for a line, three for a quadratic and four for a cubic).
+///
+/// Elliptical arcs are converted into cubic Bézier segments by `arc_to_cubics`.
+pub fn to_polybezier_loops(subpaths: &[SubPath]) -> Vec<(Vec<[f32; 2]>, Vec<usize>, bool)> {
+    let mut loops = vec![];
+    for subpath in subpaths {
+        if subpath.segments.is_empty() {
+            continue;
+        }
+        let mut vtx2xy = vec![subpath.start];
+        let mut seg2vtx = vec![0];
+        let mut add_segment = |segment: &Segment| {
+            match segment {
+                Segment::Line(p1) => vtx2xy.push(*p1),
+                Segment::Quadratic(pc, p1) => vtx2xy.extend([*pc, *p1]),
+                Segment::Cubic(pc0, pc1, p1) => vtx2xy.extend([*pc0, *pc1, *p1]),
+                Segment::Arc { .. } => unreachable!(),
+            }
+            seg2vtx.push(vtx2xy.len() - 1);
+        };
+        let mut pos_cur = subpath.start;
+        for segment in subpath.segments.iter() {
+            if let Segment::Arc {
+                radii,
+                x_axis_rotation,
+                large_arc,
+                sweep,
+                end,
+            } = segment
+            {
+                arc_to_cubics(&pos_cur, radii, *x_axis_rotation, *large_arc, *sweep, end)
+                    .iter()
+                    .for_each(&mut add_segment);
+            } else {
+                add_segment(segment);
+            }
+            pos_cur = segment.end();
+        }
+        loops.push((vtx2xy, seg2vtx, subpath.is_closed));
+    }
+    loops
+}
+
+#[test]
+fn test_parse() {
+    // numbers without separators, exponents and relative commands
+    let path = parse("M.5.5L1e1-2e-1,3.5E+1.5 l-1-1").unwrap();
+    assert_eq!(path.len(), 1);
+    assert_eq!(path[0].start, [0.5, 0.5]);
+    assert_eq!(
+        path[0].segments,
+        vec![
+            Segment::Line([10., -0.2]),
+            Segment::Line([35., 0.5]),
+            Segment::Line([34., -0.5])
+        ]
+    );
+    // implicit lineto after moveto, sub-path started after closepath without moveto
+    let path = parse("m 1 1 2 0 0 2 z l 1 0 z").unwrap();
+    assert_eq!(path.len(), 2);
+    assert_eq!(path[0].end(), [3., 3.]);
+    assert_eq!(path[1].start, [1., 1.]);
+    assert_eq!(path[1].segments, vec![Segment::Line([2., 1.])]);
+    // smooth curves reflect the previous control point
+    let path = parse("M0 0 C 0 1 1 1 1 0 S 2 -1 2 0 Q 3 1 4 0 T 6 0 s 1 1 1 0").unwrap();
+    assert_eq!(
+        path[0].segments[1],
+        Segment::Cubic([1., -1.], [2., -1.], [2., 0.])
+    );
+    assert_eq!(path[0].segments[3], Segment::Quadratic([5., -1.], [6., 0.]));
+    assert_eq!(
+        path[0].segments[4],
+        Segment::Cubic([6., 0.], [7., 1.], [7., 0.])
+    );
+    // arc flags without separators
+    let path = parse("M0,0a5,5 30 1110,0").unwrap();
+    assert_eq!(
+        path[0].segments,
+        vec![Segment::Arc {
+            radii: [5., 5.],
+            x_axis_rotation: 30.,
+            large_arc: true,
+            sweep: true,
+            end: [10., 0.]
+        }]
+    );
+    assert_eq!(parse("").unwrap(), vec![]);
+    // errors with the position
+    let err = |d: &str| parse(d).unwrap_err();
+    assert_eq!(err("L 0 0").kind, ParseErrorKind::MissingMoveTo);
+    assert_eq!(
+        err("M 0 0 L 1"),
+        ParseError {
+            position: 9,
+            kind: ParseErrorKind::UnexpectedEnd
+        }
+    );
+    assert_eq!(
+        err("M 0 0 X 1 1"),
+        ParseError {
+            position: 6,
+            kind: ParseErrorKind::UnexpectedCharacter('X')
+        }
+    );
+    assert_eq!(
+        err("M 0 0 A 1 1 0 2 0 1 1").kind,
+        ParseErrorKind::ExpectedFlag
+    );
+    assert_eq!(err("M 0 0 L . 1").kind, ParseErrorKind::ExpectedNumber);
+}
+
+#[test]
+fn test_arc_to_cubics() {
+    use del_geo_core::vec2::Vec2;
+    let check_on_circle = |p0: &[f32; 2], segs: &[Segment], center: &[f32; 2], rad: f32| {
+        let mut ps = *p0;
+        for seg in segs {
+            let Segment::Cubic(pc0, pc1, pe) = seg else {
+                panic!();
+            };
+            for i in 0..=10 {
+                let t = i as f32 / 10.;
+                let p = del_geo_core::bezier_cubic::eval(&ps, pc0, pc1, pe, t);
+                assert!((p.sub(center).norm() - rad).abs() < 1.0e-3 * rad);
+            }
+            ps = *pe;
+        }
+    };
+    // half circle passing through `y=-1` with the sweep flag
+    let segs = arc_to_cubics(&[0., 0.], &[1., 1.], 0., false, true, &[2., 0.]);
+    assert_eq!(segs.len(), 2);
+    assert!(segs[0].end().sub(&[1., -1.]).norm() < 1.0e-5);
+    check_on_circle(&[0., 0.], &segs, &[1., 0.], 1.);
+    // too small radii are scaled up
+    let segs = arc_to_cubics(&[0., 0.], &[0.5, 0.5], 0., false, false, &[2., 0.]);
+    assert!(segs[0].end().sub(&[1., 1.]).norm() < 1.0e-5);
+    check_on_circle(&[0., 0.], &segs, &[1., 0.], 1.);
+    // three quarters of the circle centered at the origin
+    let segs = arc_to_cubics(&[1., 0.], &[1., 1.], 0., true, true, &[0., -1.]);
+    assert_eq!(segs.len(), 3);
+    check_on_circle(&[1., 0.], &segs, &[0., 0.], 1.);
+    // rotated ellipse ends exactly at the end point
+    let segs = arc_to_cubics(&[0., 0.], &[2., 1.], 30., false, true, &[1., 1.]);
+    assert_eq!(segs.last().unwrap().end(), [1., 1.]);
+    // degenerated cases
+    assert!(arc_to_cubics(&[1., 1.], &[1., 1.], 0., false, false, &[1., 1.]).is_empty());
+    assert_eq!(
+        arc_to_cubics(&[0., 0.], &[0., 1.], 0., false, false, &[1., 1.]),
+        vec![Segment::Line([1., 1.])]
+    );
+    // arcs in the path data
+    let path = parse("M 0 0 A 1 1 0 0 1 2 0 z").unwrap();
+    let loops = to_polybezier_loops(&path);
+    assert_eq!(loops[0].1, vec![0, 3, 6]);
+}